@@ -8,7 +8,7 @@ fn main() -> Result<(), String> {
     println!("==============================================\n");
 
     let printer_ip = "10.73.27.7";
-    let printer = ZplPrinter::new(printer_ip.to_string(), 9100);
+    let printer = ZplPrinter::new("Status query".to_string(), printer_ip.to_string(), 9100);
 
     println!("Querying printer at {}...\n", printer_ip);
 
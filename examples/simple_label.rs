@@ -1,13 +1,11 @@
-use zebras::{
-    printer::{ZplPrinter, send_to_printer},
-    zpl::{FontOrientation, ZplCommand, commands_to_zpl},
-};
+use zebras::zpl::{FontName, FontOrientation, ZplCommand, commands_to_zpl};
 
 fn main() -> Result<(), String> {
     let commands = vec![
         ZplCommand::StartFormat,
         ZplCommand::FieldOrigin { x: 50, y: 50 },
         ZplCommand::Font {
+            font: FontName::default(),
             orientation: FontOrientation::Normal,
             height: 50,
             width: 50,
@@ -27,6 +25,7 @@ fn main() -> Result<(), String> {
         ZplCommand::FieldSeparator,
         ZplCommand::FieldOrigin { x: 50, y: 200 },
         ZplCommand::Font {
+            font: FontName::default(),
             orientation: FontOrientation::Normal,
             height: 30,
             width: 30,
@@ -45,8 +44,10 @@ fn main() -> Result<(), String> {
     println!();
 
     println!("To send to printer, uncomment the code below:");
-    println!("// let printer = ZplPrinter::new(\"10.73.27.7\".to_string(), 9100);");
-    println!("// send_to_printer(&printer, &zpl)?;");
+    println!(
+        "// let printer = zebras::printer::ZplPrinter::new(\"Label printer\".to_string(), \"10.73.27.7\".to_string(), 9100);"
+    );
+    println!("// zebras::printer::send_to_printer(&printer, &zpl)?;");
 
     Ok(())
 }
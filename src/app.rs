@@ -1,22 +1,40 @@
-use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, Mutex};
 
 use zebras::{
+    barcode::validate_label_barcodes,
+    font::TrueTypeFont,
+    graphic::{
+        DitherMode, ImageConversion, encode_graphic_data, extract_graphics, graphic_to_image,
+        image_to_zpl_hex_with,
+    },
     labelary::LabelaryClient,
-    printer::ZplPrinter,
+    lint::{Diagnostic, Severity, lint_commands, lint_zpl},
+    parser::parse_zpl,
+    printer::{PrintProfile, ZplPrinter},
     printer_status::*,
-    zpl::{FieldOrientation, FontOrientation, ZplCommand, commands_to_zpl},
+    renderer::LabelRenderer,
+    serial::with_print_quantity,
+    stored_format::StoredFormat,
+    template::{Record, bind_commands, records_from_csv, records_from_json, template_variables},
+    text::{TextStyle, ZplFont, shrink_to_fit, truncate_to_fit},
+    units::{Resolution, rescale_commands},
+    zpl::{
+        ClockMode, Code128Mode, FieldOrientation, FontDownload, FontName, FontOrientation,
+        GraphicEncoding, KioskPresentType, MediaMode, MediaTracking, MediaType, QrErrorCorrection,
+        ZplCommand, ZplPrefix, commands_to_zpl, is_font_letter,
+    },
 };
 
 const LOGO_BYTES: &[u8] = include_bytes!("../logomark-white.png");
 
+type PendingResult<T> = Arc<Mutex<Option<Result<T, String>>>>;
+
 #[derive(Debug, Clone)]
 pub struct CsvOrder {
     promise_time: String,
-    submit_time: String,
-    order_id: String,
-    entree_id: String,
-    quantity: String,
     ingredients: HashMap<String, String>,
 }
 
@@ -101,6 +119,12 @@ impl CsvOrder {
     }
 }
 
+const STORED_FORMAT_NAME: &str = "R:BOWL.ZPL";
+const STORED_FORMAT_INGREDIENT_SLOTS: usize = 20;
+const GRAPHIC_THUMBNAIL_SIZE: f32 = 128.0;
+const MAX_DIAGNOSTIC_ROWS: usize = 5;
+const DESIGN_RESOLUTION: Resolution = Resolution::Dpmm8;
+
 pub struct LabelConfig {
     title: String,
     date: String,
@@ -127,6 +151,21 @@ impl Default for LabelConfig {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PreviewRenderer {
+    Local,
+    Labelary,
+}
+
+impl PreviewRenderer {
+    fn label(&self) -> &'static str {
+        match self {
+            PreviewRenderer::Local => "Local",
+            PreviewRenderer::Labelary => "Labelary",
+        }
+    }
+}
+
 pub struct Zebras {
     label_config: LabelConfig,
     zpl_commands: Vec<ZplCommand>,
@@ -135,7 +174,7 @@ pub struct Zebras {
     error_message: Option<String>,
     is_loading: bool,
     needs_initial_render: bool,
-    pending_response: Arc<Mutex<Option<Result<Vec<u8>, String>>>>,
+    pending_response: PendingResult<Vec<u8>>,
     show_raw_text: bool,
     raw_zpl_mode: bool,
     raw_zpl_input: String,
@@ -144,9 +183,10 @@ pub struct Zebras {
     print_status: Option<String>,
     manual_ip: String,
     image_load_status: Option<String>,
-    graphic_threshold: u8,
+    graphic_conversion: ImageConversion,
+    graphic_thumbnails: HashMap<usize, (u64, egui::TextureHandle)>,
     needs_render_after_image: bool,
-    pending_query_result: Arc<Mutex<Option<Result<String, String>>>>,
+    pending_query_result: PendingResult<String>,
     query_response: Option<String>,
     is_querying: bool,
     parsed_status: Option<PrinterStatus>,
@@ -156,6 +196,13 @@ pub struct Zebras {
     print_copies: u32,
     csv_orders: Vec<CsvOrder>,
     current_order_index: usize,
+    data_template: Vec<ZplCommand>,
+    data_records: Vec<Record>,
+    current_record_index: usize,
+    use_stored_format: bool,
+    format_on_printer: bool,
+    preview_renderer: PreviewRenderer,
+    preview_label_index: u64,
 }
 
 impl Default for Zebras {
@@ -181,7 +228,8 @@ impl Default for Zebras {
             print_status: None,
             manual_ip: "10.73.27.7".to_string(),
             image_load_status: None,
-            graphic_threshold: 128,
+            graphic_conversion: ImageConversion::default(),
+            graphic_thumbnails: HashMap::new(),
             needs_render_after_image: false,
             pending_query_result: Arc::new(Mutex::new(None)),
             query_response: None,
@@ -193,6 +241,13 @@ impl Default for Zebras {
             print_copies: 1,
             csv_orders: Vec::new(),
             current_order_index: 0,
+            data_template: Vec::new(),
+            data_records: Vec::new(),
+            current_record_index: 0,
+            use_stored_format: false,
+            format_on_printer: false,
+            preview_renderer: PreviewRenderer::Local,
+            preview_label_index: 0,
         }
     }
 }
@@ -221,6 +276,7 @@ impl Zebras {
             ZplCommand::FieldSeparator,
             ZplCommand::FieldOrigin { x: 450, y: 60 },
             ZplCommand::Font {
+                font: FontName::default(),
                 orientation: FontOrientation::Normal,
                 height: 40,
                 width: 40,
@@ -234,10 +290,12 @@ impl Zebras {
                 width: 400,
                 height: 86,
                 data: logo_hex.to_string(),
+                encoding: GraphicEncoding::Auto,
             },
             ZplCommand::FieldSeparator,
             ZplCommand::FieldOrigin { x: 100, y: 125 },
             ZplCommand::Font {
+                font: FontName::default(),
                 orientation: FontOrientation::Normal,
                 height: 30,
                 width: 30,
@@ -248,6 +306,7 @@ impl Zebras {
             ZplCommand::FieldSeparator,
             ZplCommand::FieldOrigin { x: 100, y: 170 },
             ZplCommand::Font {
+                font: FontName::default(),
                 orientation: FontOrientation::Normal,
                 height: 35,
                 width: 35,
@@ -261,35 +320,38 @@ impl Zebras {
         let mut column1_y = 250;
         let mut column2_y = 250;
         let ingredient_spacing = 50;
+        let ingredient_style = TextStyle::scalable(40, 30);
+        let column1_width = 320;
+        let column2_width = 332;
 
         for (index, ingredient) in config.ingredients.iter().enumerate() {
-            let column1_items = (config.ingredients.len() + 1) / 2;
-
-            if index < column1_items {
-                commands.push(ZplCommand::FieldOrigin { x: 120, y: column1_y });
-                commands.push(ZplCommand::Font {
-                    orientation: FontOrientation::Normal,
-                    height: 40,
-                    width: 30,
-                });
-                commands.push(ZplCommand::FieldData {
-                    data: ingredient.clone(),
-                });
-                commands.push(ZplCommand::FieldSeparator);
+            let column1_items = config.ingredients.len().div_ceil(2);
+
+            let (x, y, column_width) = if index < column1_items {
+                let position = (120, column1_y, column1_width);
                 column1_y += ingredient_spacing;
+                position
             } else {
-                commands.push(ZplCommand::FieldOrigin { x: 450, y: column2_y });
-                commands.push(ZplCommand::Font {
-                    orientation: FontOrientation::Normal,
-                    height: 40,
-                    width: 30,
-                });
-                commands.push(ZplCommand::FieldData {
-                    data: ingredient.clone(),
-                });
-                commands.push(ZplCommand::FieldSeparator);
+                let position = (450, column2_y, column2_width);
                 column2_y += ingredient_spacing;
-            }
+                position
+            };
+
+            let style = shrink_to_fit(ingredient, ingredient_style, column_width, 28);
+            commands.push(ZplCommand::FieldOrigin {
+                x,
+                y: y + ingredient_style.height - style.height,
+            });
+            commands.push(ZplCommand::Font {
+                font: FontName::default(),
+                orientation: FontOrientation::Normal,
+                height: style.height,
+                width: style.width,
+            });
+            commands.push(ZplCommand::FieldData {
+                data: truncate_to_fit(ingredient, style, column_width),
+            });
+            commands.push(ZplCommand::FieldSeparator);
         }
 
         commands.push(ZplCommand::EndFormat);
@@ -320,21 +382,20 @@ impl Zebras {
                 .pick_file()
             {
                 match std::fs::read_to_string(&path) {
-                    Ok(contents) => {
-                        match self.parse_csv(&contents) {
-                            Ok(orders) => {
-                                self.csv_orders = orders;
-                                self.current_order_index = 0;
-                                if !self.csv_orders.is_empty() {
-                                    self.load_order_at_index(0);
-                                }
-                                self.print_status = Some(format!("Loaded {} orders from CSV", self.csv_orders.len()));
-                            }
-                            Err(error) => {
-                                self.print_status = Some(format!("Failed to parse CSV: {}", error));
+                    Ok(contents) => match self.parse_csv(&contents) {
+                        Ok(orders) => {
+                            self.csv_orders = orders;
+                            self.current_order_index = 0;
+                            if !self.csv_orders.is_empty() {
+                                self.load_order_at_index(0);
                             }
+                            self.print_status =
+                                Some(format!("Loaded {} orders from CSV", self.csv_orders.len()));
                         }
-                    }
+                        Err(error) => {
+                            self.print_status = Some(format!("Failed to parse CSV: {}", error));
+                        }
+                    },
                     Err(error) => {
                         self.print_status = Some(format!("Failed to read file: {}", error));
                     }
@@ -350,7 +411,8 @@ impl Zebras {
 
     fn parse_csv(&self, contents: &str) -> Result<Vec<CsvOrder>, String> {
         let mut reader = csv::Reader::from_reader(contents.as_bytes());
-        let headers = reader.headers()
+        let headers = reader
+            .headers()
             .map_err(|e| format!("Failed to read CSV headers: {}", e))?
             .clone();
 
@@ -361,20 +423,17 @@ impl Zebras {
 
             let mut order = CsvOrder {
                 promise_time: record.get(0).unwrap_or("").to_string(),
-                submit_time: record.get(1).unwrap_or("").to_string(),
-                order_id: record.get(2).unwrap_or("").to_string(),
-                entree_id: record.get(3).unwrap_or("").to_string(),
-                quantity: record.get(4).unwrap_or("").to_string(),
                 ingredients: HashMap::new(),
             };
 
             for (index, value) in record.iter().enumerate() {
-                if index >= 5 {
-                    if let Some(header) = headers.get(index) {
-                        if !value.is_empty() {
-                            order.ingredients.insert(header.to_string(), value.to_string());
-                        }
-                    }
+                if index >= 5
+                    && let Some(header) = headers.get(index)
+                    && !value.is_empty()
+                {
+                    order
+                        .ingredients
+                        .insert(header.to_string(), value.to_string());
                 }
             }
 
@@ -384,6 +443,109 @@ impl Zebras {
         Ok(orders)
     }
 
+    fn load_data_records(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let variables = match template_variables(&self.zpl_commands) {
+                Ok(variables) => variables,
+                Err(error) => {
+                    self.print_status = Some(format!("Invalid template: {}", error));
+                    return;
+                }
+            };
+            if variables.is_empty() {
+                self.print_status =
+                    Some("Add {{placeholders}} to field data before loading data".to_string());
+                return;
+            }
+
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Data", &["csv", "json"])
+                .pick_file()
+            {
+                let is_json = path
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+                let records = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read file: {}", e))
+                    .and_then(|contents| {
+                        if is_json {
+                            records_from_json(&contents)
+                        } else {
+                            records_from_csv(&contents)
+                        }
+                    });
+                match records {
+                    Ok(records) => {
+                        self.data_template = self.zpl_commands.clone();
+                        self.data_records = records;
+                        self.print_status = Some(format!(
+                            "Loaded {} records for {}",
+                            self.data_records.len(),
+                            variables.join(", ")
+                        ));
+                        self.load_record_at_index(0);
+                    }
+                    Err(error) => self.print_status = Some(error),
+                }
+            }
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.print_status = Some("Data load not available in WASM".to_string());
+        }
+    }
+
+    fn load_record_at_index(&mut self, index: usize) -> bool {
+        let Some(record) = self.data_records.get(index) else {
+            return false;
+        };
+        self.current_record_index = index;
+        match bind_commands(&self.data_template, record) {
+            Ok(commands) => {
+                self.zpl_commands = commands;
+                self.is_dirty = true;
+                true
+            }
+            Err(error) => {
+                self.print_status = Some(format!("Record {}: {}", index + 1, error));
+                false
+            }
+        }
+    }
+
+    fn close_data_records(&mut self) {
+        self.zpl_commands = std::mem::take(&mut self.data_template);
+        self.data_records.clear();
+        self.current_record_index = 0;
+        self.is_dirty = true;
+    }
+
+    fn print_all_records(&mut self) {
+        if self.selected_printer.is_none() {
+            self.print_status = Some("No printer selected".to_string());
+            return;
+        }
+
+        let saved_index = self.current_record_index;
+        let mut sent = 0;
+        for index in 0..self.data_records.len() {
+            if !self.load_record_at_index(index) {
+                break;
+            }
+            self.send_to_printer();
+            sent += 1;
+        }
+        let status = self.print_status.take();
+        self.load_record_at_index(saved_index);
+        self.print_status = if sent == self.data_records.len() {
+            Some(format!("Sent {} records to printer", sent))
+        } else {
+            status
+        };
+    }
+
     fn load_order_at_index(&mut self, index: usize) {
         if index < self.csv_orders.len() {
             self.current_order_index = index;
@@ -421,6 +583,11 @@ impl Zebras {
             return;
         }
 
+        if self.use_stored_format {
+            self.print_csv_orders_with_stored_format();
+            return;
+        }
+
         let total_orders = self.csv_orders.len();
         let saved_index = self.current_order_index;
 
@@ -433,6 +600,100 @@ impl Zebras {
         self.print_status = Some(format!("Sent {} labels to printer", total_orders));
     }
 
+    fn stored_format_template(&self) -> Result<StoredFormat, String> {
+        let placeholders = LabelConfig {
+            title: self.label_config.title.clone(),
+            date: "{{date}}".to_string(),
+            bowl_description: "{{bowl_description}}".to_string(),
+            ingredients: (1..=STORED_FORMAT_INGREDIENT_SLOTS)
+                .map(|slot| format!("{{{{ingredient_{}}}}}", slot))
+                .collect(),
+        };
+        let template = Zebras::build_commands_from_config(&placeholders, &Zebras::load_logo_hex());
+        StoredFormat::from_template(STORED_FORMAT_NAME, &template)
+    }
+
+    fn stored_format_values(config: &LabelConfig) -> HashMap<String, String> {
+        let mut values: HashMap<String, String> = (1..=STORED_FORMAT_INGREDIENT_SLOTS)
+            .map(|slot| (format!("ingredient_{}", slot), String::new()))
+            .collect();
+        values.insert("date".to_string(), config.date.clone());
+        values.insert(
+            "bowl_description".to_string(),
+            config.bowl_description.clone(),
+        );
+
+        let column1_items = config.ingredients.len().div_ceil(2);
+        let column2_start = STORED_FORMAT_INGREDIENT_SLOTS.div_ceil(2);
+        for (index, ingredient) in config.ingredients.iter().enumerate() {
+            let slot = if index < column1_items {
+                index
+            } else {
+                column2_start + index - column1_items
+            };
+            if slot < STORED_FORMAT_INGREDIENT_SLOTS {
+                values.insert(format!("ingredient_{}", slot + 1), ingredient.clone());
+            }
+        }
+        values
+    }
+
+    fn print_csv_orders_with_stored_format(&mut self) {
+        let Some(printer) = self.selected_printer.and_then(|idx| self.printers.get(idx)) else {
+            self.print_status = Some("No printer selected".to_string());
+            return;
+        };
+
+        let mut format = match self.stored_format_template() {
+            Ok(format) => format,
+            Err(e) => {
+                self.print_status = Some(format!("Stored format error: {}", e));
+                return;
+            }
+        };
+        format.format = rescale_commands(
+            &format.format,
+            DESIGN_RESOLUTION,
+            printer.profile.resolution,
+        );
+        let mut zpl = printer.profile.to_zpl();
+        zpl.push('\n');
+        if !self.format_on_printer {
+            zpl.push_str(&format.download_zpl());
+            zpl.push('\n');
+        }
+
+        for (index, order) in self.csv_orders.iter().enumerate() {
+            let values = Zebras::stored_format_values(&order.to_label_config(index + 1));
+            let job = match format.job_zpl(&values) {
+                Ok(job) => job,
+                Err(e) => {
+                    self.print_status = Some(format!("Order {}: {}", index + 1, e));
+                    return;
+                }
+            };
+            for _ in 0..self.print_copies {
+                zpl.push_str(&job);
+                zpl.push('\n');
+            }
+        }
+
+        match zebras::printer::send_to_printer(printer, &zpl) {
+            Ok(_) => {
+                self.format_on_printer = true;
+                self.print_status = Some(format!(
+                    "Sent {} data-only labels to {} ({})",
+                    self.csv_orders.len(),
+                    printer.name,
+                    STORED_FORMAT_NAME
+                ));
+            }
+            Err(e) => {
+                self.print_status = Some(format!("Print error: {}", e));
+            }
+        }
+    }
+
     fn randomize_ingredients(&mut self) {
         let all_ingredients = vec![
             "* White Rice",
@@ -478,7 +739,8 @@ impl Zebras {
             .map(|(_, index)| all_ingredients[*index].to_string())
             .collect();
 
-        self.label_config.bowl_description = format!("Bowl #{} ({} Ingredients)",
+        self.label_config.bowl_description = format!(
+            "Bowl #{} ({} Ingredients)",
             (seed % 100) + 1,
             ingredient_count
         );
@@ -494,6 +756,20 @@ impl Zebras {
         }
     }
 
+    fn load_raw_zpl_into_editor(&mut self) -> bool {
+        match parse_zpl(&self.raw_zpl_input) {
+            Ok(commands) => {
+                self.zpl_commands = commands;
+                self.is_dirty = true;
+                self.error_message = None;
+                true
+            }
+            Err(error) => {
+                self.error_message = Some(format!("Failed to parse ZPL: {}", error));
+                false
+            }
+        }
+    }
 
     fn save_template(&mut self) {
         #[cfg(not(target_arch = "wasm32"))]
@@ -560,22 +836,54 @@ impl Zebras {
     }
 
     fn send_to_printer(&mut self) {
+        let commands = if self.raw_zpl_mode {
+            parse_zpl(&self.raw_zpl_input).unwrap_or_default()
+        } else {
+            self.zpl_commands.clone()
+        };
+        if let Some((index, error)) = validate_label_barcodes(&commands).into_iter().next() {
+            self.print_status = Some(format!(
+                "Not sent: barcode data in command #{} is invalid: {}",
+                index + 1,
+                error
+            ));
+            return;
+        }
+
         if let Some(idx) = self.selected_printer {
             if let Some(printer) = self.printers.get(idx) {
                 let mut zpl = String::new();
 
-                zpl.push_str("^XA^MMT^XZ\n");
+                zpl.push_str(&printer.profile.to_zpl());
+                zpl.push('\n');
 
-                let label_zpl = self.get_zpl_text();
-
-                for _ in 0..self.print_copies {
-                    zpl.push_str(&label_zpl);
+                let resolution = printer.profile.resolution;
+                let commands = rescale_commands(&commands, DESIGN_RESOLUTION, resolution);
+                if commands.iter().any(ZplCommand::is_serialized) {
+                    zpl.push_str(&commands_to_zpl(&with_print_quantity(
+                        &commands,
+                        self.print_copies,
+                    )));
                     zpl.push('\n');
+                } else {
+                    let label_zpl = if resolution == DESIGN_RESOLUTION {
+                        self.get_zpl_text()
+                    } else {
+                        commands_to_zpl(&commands)
+                    };
+
+                    for _ in 0..self.print_copies {
+                        zpl.push_str(&label_zpl);
+                        zpl.push('\n');
+                    }
                 }
 
                 match zebras::printer::send_to_printer(printer, &zpl) {
                     Ok(_) => {
-                        self.print_status = Some(format!("Sent {} copies to {}", self.print_copies, printer.name));
+                        self.print_status = Some(format!(
+                            "Sent {} copies to {}",
+                            self.print_copies, printer.name
+                        ));
                     }
                     Err(e) => {
                         self.print_status = Some(format!("Print error: {}", e));
@@ -587,6 +895,18 @@ impl Zebras {
         }
     }
 
+    fn sync_printer_clock(&mut self) {
+        let Some(printer) = self.selected_printer.and_then(|idx| self.printers.get(idx)) else {
+            self.print_status = Some("No printer selected".to_string());
+            return;
+        };
+
+        self.print_status = Some(match zebras::printer::sync_printer_clock(printer) {
+            Ok(_) => format!("Clock synced on {}", printer.name),
+            Err(e) => format!("Clock sync error: {}", e),
+        });
+    }
+
     fn add_manual_printer(&mut self) {
         let ip = self.manual_ip.trim();
 
@@ -602,7 +922,7 @@ impl Zebras {
 
         #[cfg(not(target_arch = "wasm32"))]
         {
-            let printer = ZplPrinter::new(ip.to_string(), 9100);
+            let printer = ZplPrinter::new(format!("ZPL Printer @ {}", ip), ip.to_string(), 9100);
 
             if !self.printers.iter().any(|p| p.ip == ip) {
                 self.printers.push(printer);
@@ -744,9 +1064,9 @@ impl Zebras {
                                 let current = guard
                                     .as_ref()
                                     .and_then(|r| r.as_ref().ok())
-                                    .map(|s| s.clone())
+                                    .cloned()
                                     .unwrap_or_else(|| {
-                                        format!("Starting comprehensive query...\n\n")
+                                        "Starting comprehensive query...\n\n".to_string()
                                     });
 
                                 let is_complete = index == total - 1;
@@ -778,13 +1098,24 @@ impl Zebras {
 
     fn render_zpl(&mut self, ctx: &egui::Context) {
         self.error_message = None;
+
+        if self.preview_renderer == PreviewRenderer::Local {
+            self.render_zpl_locally(ctx);
+            return;
+        }
+
         self.is_loading = true;
 
         let zpl = self.get_zpl_text();
 
         let ctx = ctx.clone();
         let pending_response = Arc::clone(&self.pending_response);
-        let client = LabelaryClient::default();
+        let commands = if self.raw_zpl_mode {
+            parse_zpl(&self.raw_zpl_input).unwrap_or_default()
+        } else {
+            self.zpl_commands.clone()
+        };
+        let client = LabelaryClient::for_commands(DESIGN_RESOLUTION.dpmm(), &commands);
 
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -811,120 +1142,809 @@ impl Zebras {
         self.is_dirty = false;
     }
 
+    fn render_zpl_locally(&mut self, ctx: &egui::Context) {
+        let result = if self.raw_zpl_mode {
+            parse_zpl(&self.raw_zpl_input)
+        } else {
+            Ok(self.zpl_commands.clone())
+        };
+
+        match result {
+            Ok(commands) => {
+                let image = LabelRenderer::for_commands(DESIGN_RESOLUTION.dpmm(), &commands)
+                    .label_index(self.preview_label_index)
+                    .render(&commands);
+                self.show_rendered_image(image::DynamicImage::ImageLuma8(image), ctx);
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Failed to render ZPL: {}", e));
+            }
+        }
+
+        self.is_loading = false;
+        self.is_dirty = false;
+    }
+
     fn render_command_editor(&mut self, ui: &mut egui::Ui, idx: usize) {
         let command = &mut self.zpl_commands[idx];
         match command {
             ZplCommand::StartFormat
             | ZplCommand::EndFormat
             | ZplCommand::FieldSeparator
-            | ZplCommand::MediaModeDelayed
-            | ZplCommand::MediaModeTearOff
-            | ZplCommand::CutNow => {}
-            ZplCommand::FieldOrigin { x, y } => {
+            | ZplCommand::FieldReverse
+            | ZplCommand::DelayedCut => {}
+            ZplCommand::PrintWidth { width } => {
+                ui.horizontal(|ui| {
+                    ui.label("Width (dots):");
+                    if ui
+                        .add(egui::DragValue::new(width).speed(1).range(2..=32000))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::LabelLength { length } => {
+                ui.horizontal(|ui| {
+                    ui.label("Length (dots):");
+                    if ui
+                        .add(egui::DragValue::new(length).speed(1).range(1..=32000))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::LabelHome { x, y } => {
                 ui.horizontal(|ui| {
                     ui.label("X:");
-                    if ui.add(egui::DragValue::new(x).speed(1)).lost_focus() {
+                    if ui
+                        .add(egui::DragValue::new(x).speed(1).range(0..=32000))
+                        .lost_focus()
+                    {
                         self.is_dirty = true;
                     }
                     ui.label("Y:");
-                    if ui.add(egui::DragValue::new(y).speed(1)).lost_focus() {
+                    if ui
+                        .add(egui::DragValue::new(y).speed(1).range(0..=32000))
+                        .lost_focus()
+                    {
                         self.is_dirty = true;
                     }
                 });
             }
-            ZplCommand::Font { height, width, .. } => {
+            ZplCommand::LabelShift { shift } => {
                 ui.horizontal(|ui| {
-                    ui.label("Height:");
-                    if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
+                    ui.label("Shift Left (dots):");
+                    if ui
+                        .add(egui::DragValue::new(shift).speed(1).range(-9999..=9999))
+                        .lost_focus()
+                    {
                         self.is_dirty = true;
                     }
-                    ui.label("Width:");
-                    if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
+                });
+            }
+            ZplCommand::LabelTop { offset } => {
+                ui.horizontal(|ui| {
+                    ui.label("Top Offset (dot rows):");
+                    if ui
+                        .add(egui::DragValue::new(offset).speed(1).range(-120..=120))
+                        .lost_focus()
+                    {
                         self.is_dirty = true;
                     }
                 });
             }
-            ZplCommand::FieldData { data } => {
+            ZplCommand::PrintOrientation { inverted } => {
                 ui.horizontal(|ui| {
-                    ui.label("Text:");
-                    if ui.text_edit_singleline(data).lost_focus() {
+                    ui.label("Orientation:");
+                    if ui.radio_value(inverted, false, "Normal").changed() {
+                        self.is_dirty = true;
+                    }
+                    if ui.radio_value(inverted, true, "Inverted").changed() {
                         self.is_dirty = true;
                     }
                 });
             }
-            ZplCommand::GraphicBox {
-                width,
-                height,
-                thickness,
-                color,
-                rounding,
+            ZplCommand::PrintRate {
+                print_speed,
+                slew_speed,
+                backfeed_speed,
             } => {
-                ui.push_id(idx, |ui| {
-                    ui.vertical(|ui| {
-                        ui.horizontal(|ui| {
-                            ui.label("W:");
-                            if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
-                                self.is_dirty = true;
-                            }
-                            ui.label("H:");
-                            if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
-                                self.is_dirty = true;
-                            }
-                            ui.label("T:");
-                            if ui.add(egui::DragValue::new(thickness).speed(1)).lost_focus() {
-                                self.is_dirty = true;
-                            }
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label("Color:");
-                            let mut color_selection = match color {
-                                Some('B') => 0,
-                                Some('W') => 1,
-                                _ => 2,
-                            };
-                            let prev_selection = color_selection;
-                            ui.radio_value(&mut color_selection, 0, "Black");
-                            ui.radio_value(&mut color_selection, 1, "White");
-                            ui.radio_value(&mut color_selection, 2, "Default");
-                            if color_selection != prev_selection {
-                                *color = match color_selection {
-                                    0 => Some('B'),
-                                    1 => Some('W'),
-                                    _ => None,
-                                };
-                                self.is_dirty = true;
-                            }
-                        });
-                        ui.horizontal(|ui| {
-                            ui.label("Rounding:");
-                            let mut rounding_value = rounding.unwrap_or(0);
-                            let response = ui.add(egui::Slider::new(&mut rounding_value, 0..=8));
-                            if response.changed() {
-                                *rounding = if rounding_value > 0 {
-                                    Some(rounding_value)
-                                } else {
-                                    None
-                                };
-                            }
-                            if response.lost_focus() {
-                                self.is_dirty = true;
-                            }
-                        });
-                        ui.label(egui::RichText::new("Tip: For horizontal line, set height=1-5. For vertical line, set width=1-5").small().color(egui::Color32::GRAY));
-                    });
+                ui.horizontal(|ui| {
+                    ui.label("Print Speed (in/s):");
+                    if ui
+                        .add(egui::DragValue::new(print_speed).speed(1).range(1..=14))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if optional_value_editor(ui, "Slew Speed:", slew_speed, 1..=14) {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if optional_value_editor(ui, "Backfeed Speed:", backfeed_speed, 1..=14) {
+                        self.is_dirty = true;
+                    }
                 });
             }
-            ZplCommand::GraphicField {
-                width,
-                height,
-                data,
-            } => {
-                ui.vertical(|ui| {
-                    ui.label(
-                        egui::RichText::new("Note: Add Field Origin (^FO) command before this")
-                            .small()
-                            .color(egui::Color32::GRAY),
-                    );
+            ZplCommand::MediaDarkness { adjustment } => {
+                ui.horizontal(|ui| {
+                    ui.label("Darkness Adjustment:");
+                    if ui
+                        .add(egui::DragValue::new(adjustment).speed(1).range(-30..=30))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::SetDarkness { darkness } => {
+                ui.horizontal(|ui| {
+                    ui.label("Darkness:");
+                    if ui
+                        .add(egui::DragValue::new(darkness).speed(1).range(0..=30))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::MediaType { media_type } => {
+                ui.horizontal(|ui| {
+                    ui.label("Media:");
+                    if ui
+                        .radio_value(media_type, MediaType::DirectThermal, "Direct Thermal")
+                        .changed()
+                    {
+                        self.is_dirty = true;
+                    }
+                    if ui
+                        .radio_value(media_type, MediaType::ThermalTransfer, "Thermal Transfer")
+                        .changed()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::MediaTracking { tracking } => {
+                ui.horizontal(|ui| {
+                    ui.label("Tracking:");
+                    for value in [
+                        MediaTracking::WebSensing,
+                        MediaTracking::MarkSensing,
+                        MediaTracking::Continuous,
+                        MediaTracking::ContinuousVariableLength,
+                        MediaTracking::AutoDetect,
+                    ] {
+                        if ui
+                            .radio_value(tracking, value, media_tracking_label(Some(value)))
+                            .changed()
+                        {
+                            self.is_dirty = true;
+                        }
+                    }
+                });
+            }
+            ZplCommand::FieldClock {
+                primary,
+                secondary,
+                tertiary,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Primary:");
+                    let mut text = primary.to_string();
+                    if ui
+                        .add(egui::TextEdit::singleline(&mut text).desired_width(20.0))
+                        .changed()
+                        && let Some(character) = text.chars().last()
+                    {
+                        *primary = character;
+                        self.is_dirty = true;
+                    }
+                    for (label, indicator) in [("Secondary:", secondary), ("Third:", tertiary)] {
+                        ui.label(label);
+                        let mut text = indicator.map(String::from).unwrap_or_default();
+                        if ui
+                            .add(egui::TextEdit::singleline(&mut text).desired_width(20.0))
+                            .changed()
+                        {
+                            *indicator = text.chars().last();
+                            self.is_dirty = true;
+                        }
+                    }
+                });
+                ui.label(
+                    egui::RichText::new("Field data codes: %m/%d/%y %H:%M, %I %p, %j, %a %b ...")
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+            }
+            ZplCommand::ClockMode { mode, language } => {
+                ui.horizontal(|ui| {
+                    ui.label("Mode:");
+                    let mut tolerance = match mode {
+                        ClockMode::Tolerance(minutes) => Some(*minutes),
+                        _ => None,
+                    };
+                    if ui
+                        .radio(*mode == ClockMode::StartTime, "Start Time")
+                        .clicked()
+                    {
+                        *mode = ClockMode::StartTime;
+                        self.is_dirty = true;
+                    }
+                    if ui.radio(*mode == ClockMode::TimeNow, "Time Now").clicked() {
+                        *mode = ClockMode::TimeNow;
+                        self.is_dirty = true;
+                    }
+                    if optional_value_editor(ui, "Tolerance (min):", &mut tolerance, 1..=999) {
+                        self.is_dirty = true;
+                    }
+                    if let Some(minutes) = tolerance {
+                        *mode = ClockMode::Tolerance(minutes);
+                    } else if matches!(mode, ClockMode::Tolerance(_)) {
+                        *mode = ClockMode::StartTime;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if optional_value_editor(ui, "Language:", language, 1..=18) {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::SetTime {
+                month,
+                day,
+                year,
+                hour,
+                minute,
+                second,
+                twenty_four_hour,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Date (M/D/Y):");
+                    for (value, range) in [(month, 1..=12), (day, 1..=31), (year, 1998..=2097)] {
+                        if ui
+                            .add(egui::DragValue::new(value).speed(1).range(range))
+                            .lost_focus()
+                        {
+                            self.is_dirty = true;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Time (H:M:S):");
+                    for (value, range) in [(hour, 0..=23), (minute, 0..=59), (second, 0..=59)] {
+                        if ui
+                            .add(egui::DragValue::new(value).speed(1).range(range))
+                            .lost_focus()
+                        {
+                            self.is_dirty = true;
+                        }
+                    }
+                    if ui.checkbox(twenty_four_hour, "24-hour").changed() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::SerialNumber {
+                start,
+                increment,
+                leading_zeros,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Start:");
+                    if ui.text_edit_singleline(start).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Increment:");
+                    if ui
+                        .add(egui::DragValue::new(increment).speed(1))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    if ui.checkbox(leading_zeros, "Leading Zeros").changed() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::SerializationField { mask, increment } => {
+                ui.horizontal(|ui| {
+                    ui.label("Mask:");
+                    if ui.text_edit_singleline(mask).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Increment:");
+                    if ui.text_edit_singleline(increment).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.label(
+                    egui::RichText::new(
+                        "Mask: D decimal, H hex, O octal, A alpha, N alphanumeric, % skip",
+                    )
+                    .small()
+                    .color(egui::Color32::GRAY),
+                );
+            }
+            ZplCommand::PrintQuantity {
+                quantity,
+                pause_count,
+                replicates,
+                override_pause,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Quantity:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(quantity)
+                                .speed(1)
+                                .range(1..=99_999_999),
+                        )
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Pause Every:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(pause_count)
+                                .speed(1)
+                                .range(0..=99_999_999),
+                        )
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Replicates:");
+                    if ui
+                        .add(
+                            egui::DragValue::new(replicates)
+                                .speed(1)
+                                .range(0..=99_999_999),
+                        )
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    if ui.checkbox(override_pause, "Override Pause").changed() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::DownloadFormat { name } | ZplCommand::RecallFormat { name } => {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    if ui.text_edit_singleline(name).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::FieldNumber { number } => {
+                ui.horizontal(|ui| {
+                    ui.label("Field Number:");
+                    if ui
+                        .add(egui::DragValue::new(number).speed(1).range(0..=9999))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::MediaMode { mode, prepeel } => {
+                ui.horizontal(|ui| {
+                    ui.label("Mode:");
+                    if media_mode_selector(ui, "media_mode_editor", mode) {
+                        self.is_dirty = true;
+                    }
+                    if ui.checkbox(prepeel, "Pre-peel").changed() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::ImmediateCut { cut } => {
+                if ui.checkbox(cut, "Cut immediately").changed() {
+                    self.is_dirty = true;
+                }
+            }
+            ZplCommand::KioskValues {
+                cut_amount,
+                cut_margin,
+                present_type,
+                present_timeout,
+                loop_length,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Cut Amount (mm):");
+                    let previous = *cut_amount;
+                    let response = ui.add(egui::DragValue::new(cut_amount).speed(1).range(0..=60));
+                    if response.changed() {
+                        *cut_amount = skip_invalid_range(*cut_amount, previous, 3..=9);
+                    }
+                    if response.lost_focus() {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Margin (mm):");
+                    if ui
+                        .add(egui::DragValue::new(cut_margin).speed(1).range(2..=9))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Present:");
+                    for (value, label) in [
+                        (KioskPresentType::Eject, "Eject"),
+                        (KioskPresentType::Retract, "Retract"),
+                        (KioskPresentType::NoMotion, "No Motion"),
+                    ] {
+                        if ui.radio_value(present_type, value, label).changed() {
+                            self.is_dirty = true;
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Timeout (s):");
+                    if ui
+                        .add(
+                            egui::DragValue::new(present_timeout)
+                                .speed(1)
+                                .range(0..=300),
+                        )
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Loop Length (mm):");
+                    let previous = *loop_length;
+                    let response =
+                        ui.add(egui::DragValue::new(loop_length).speed(1).range(0..=1023));
+                    if response.changed() {
+                        *loop_length = skip_invalid_range(*loop_length, previous, 1..=2);
+                    }
+                    if response.lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::FieldOrigin { x, y } => {
+                ui.horizontal(|ui| {
+                    ui.label("X:");
+                    if ui.add(egui::DragValue::new(x).speed(1)).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Y:");
+                    if ui.add(egui::DragValue::new(y).speed(1)).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::FieldTypeset {
+                x,
+                y,
+                justification,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("X:");
+                    if ui.add(egui::DragValue::new(x).speed(1)).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Baseline Y:");
+                    if ui.add(egui::DragValue::new(y).speed(1)).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                    if optional_value_editor(ui, "Justification:", justification, 0..=2) {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::Font {
+                font,
+                height,
+                width,
+                ..
+            } => {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Font:");
+                        if font_selector(ui, idx, font) {
+                            self.is_dirty = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Height:");
+                        if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
+                            self.is_dirty = true;
+                        }
+                        ui.label("Width:");
+                        if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
+                            self.is_dirty = true;
+                        }
+                    });
+                });
+            }
+            ZplCommand::FontAlias { alias, path } => {
+                ui.horizontal(|ui| {
+                    ui.label("Alias:");
+                    let mut letter = alias.to_string();
+                    if ui
+                        .add(egui::TextEdit::singleline(&mut letter).desired_width(20.0))
+                        .changed()
+                        && let Some(value) = letter.chars().last().filter(|c| is_font_letter(*c))
+                    {
+                        *alias = value.to_ascii_uppercase();
+                        self.is_dirty = true;
+                    }
+                    ui.label("Font:");
+                    if ui.text_edit_singleline(path).lost_focus() {
+                        *path = path.trim().to_uppercase();
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::DownloadFont { path, method, data } => {
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new("Stores a TrueType font in printer memory for ^A@")
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                    ui.horizontal(|ui| {
+                        for value in FontDownload::all() {
+                            if ui.radio_value(method, value, value.label()).changed() {
+                                if let Some((name, _)) = path.rsplit_once('.') {
+                                    *path = format!("{}.{}", name, value.extension());
+                                }
+                                self.is_dirty = true;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Path:");
+                        if ui.text_edit_singleline(path).lost_focus() {
+                            *path = path.trim().to_uppercase();
+                            self.is_dirty = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{} bytes", data.len() / 2));
+                        if ui.button("Load .ttf").clicked()
+                            && let Some(file) = rfd::FileDialog::new()
+                                .add_filter("TrueType", &["ttf", "otf"])
+                                .pick_file()
+                        {
+                            let device = path.chars().next().unwrap_or('E');
+                            let name = file
+                                .file_stem()
+                                .map(|stem| stem.to_string_lossy().replace(['-', ' '], "_"))
+                                .unwrap_or_default();
+                            match TrueTypeFont::open(&file, device, &name, *method) {
+                                Ok(font) => {
+                                    *path = font.path.clone();
+                                    if let ZplCommand::DownloadFont { data: hex, .. } =
+                                        font.download_command()
+                                    {
+                                        *data = hex;
+                                    }
+                                    self.image_load_status = Some(format!(
+                                        "Loaded {} ({} bytes)",
+                                        path,
+                                        font.data.len()
+                                    ));
+                                    self.is_dirty = true;
+                                }
+                                Err(e) => {
+                                    self.image_load_status =
+                                        Some(format!("Error loading font: {}", e))
+                                }
+                            }
+                        }
+                    });
+                    if let Some(ref status) = self.image_load_status {
+                        ui.label(egui::RichText::new(status).color(egui::Color32::LIGHT_BLUE));
+                    }
+                });
+            }
+            ZplCommand::FieldData { data } => {
+                ui.horizontal(|ui| {
+                    ui.label("Text:");
+                    if ui.text_edit_singleline(data).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::ChangeEncoding { encoding } => {
+                ui.horizontal(|ui| {
+                    ui.label("Encoding:");
+                    if ui
+                        .add(egui::DragValue::new(encoding).speed(1).range(0..=36))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    ui.label(
+                        egui::RichText::new("28 = UTF-8")
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                });
+            }
+            ZplCommand::GraphicBox {
+                width,
+                height,
+                thickness,
+                color,
+                rounding,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("W:");
+                            if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
+                                self.is_dirty = true;
+                            }
+                            ui.label("H:");
+                            if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
+                                self.is_dirty = true;
+                            }
+                            ui.label("T:");
+                            if ui.add(egui::DragValue::new(thickness).speed(1)).lost_focus() {
+                                self.is_dirty = true;
+                            }
+                        });
+                        if line_color_selector(ui, color) {
+                            self.is_dirty = true;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("Rounding:");
+                            let mut rounding_value = rounding.unwrap_or(0);
+                            let response = ui.add(egui::Slider::new(&mut rounding_value, 0..=8));
+                            if response.changed() {
+                                *rounding = if rounding_value > 0 {
+                                    Some(rounding_value)
+                                } else {
+                                    None
+                                };
+                            }
+                            if response.lost_focus() {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.label(egui::RichText::new("Tip: For horizontal line, set height=1-5. For vertical line, set width=1-5").small().color(egui::Color32::GRAY));
+                    });
+                });
+            }
+            ZplCommand::GraphicCircle {
+                diameter,
+                thickness,
+                color,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Diameter:");
+                            if ui
+                                .add(egui::DragValue::new(diameter).speed(1).range(3..=4095))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            ui.label("T:");
+                            if ui
+                                .add(egui::DragValue::new(thickness).speed(1).range(1..=4095))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                        });
+                        if line_color_selector(ui, color) {
+                            self.is_dirty = true;
+                        }
+                    });
+                });
+            }
+            ZplCommand::GraphicEllipse {
+                width,
+                height,
+                thickness,
+                color,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("W:");
+                            if ui
+                                .add(egui::DragValue::new(width).speed(1).range(3..=4095))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            ui.label("H:");
+                            if ui
+                                .add(egui::DragValue::new(height).speed(1).range(3..=4095))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            ui.label("T:");
+                            if ui
+                                .add(egui::DragValue::new(thickness).speed(1).range(1..=4095))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                        });
+                        if line_color_selector(ui, color) {
+                            self.is_dirty = true;
+                        }
+                    });
+                });
+            }
+            ZplCommand::GraphicDiagonalLine {
+                width,
+                height,
+                thickness,
+                color,
+                orientation,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("W:");
+                            if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
+                                self.is_dirty = true;
+                            }
+                            ui.label("H:");
+                            if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
+                                self.is_dirty = true;
+                            }
+                            ui.label("T:");
+                            if ui
+                                .add(egui::DragValue::new(thickness).speed(1))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Direction:");
+                            if ui.radio_value(orientation, 'R', "Right (/)").changed() {
+                                self.is_dirty = true;
+                            }
+                            if ui.radio_value(orientation, 'L', "Left (\\)").changed() {
+                                self.is_dirty = true;
+                            }
+                        });
+                        if line_color_selector(ui, color) {
+                            self.is_dirty = true;
+                        }
+                    });
+                });
+            }
+            ZplCommand::GraphicField {
+                width,
+                height,
+                data,
+                encoding,
+            } => {
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new("Note: Add Field Origin (^FO) command before this")
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
                     ui.horizontal(|ui| {
                         ui.label("W:");
                         if ui.add(egui::DragValue::new(width).speed(1)).lost_focus() {
@@ -942,55 +1962,81 @@ impl Zebras {
                         }
                     });
                     ui.label(format!("Data length: {} chars", data.len()));
+                    graphic_thumbnail(ui, &mut self.graphic_thumbnails, idx, *width, *height, data);
+                    ui.horizontal(|ui| {
+                        if graphic_encoding_selector(ui, idx, encoding) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(format!(
+                            "{} chars encoded",
+                            encode_graphic_data(data, width.div_ceil(8), *encoding).len()
+                        ));
+                    });
+                    if graphic_file_buttons(ui, width, height, data, &mut self.image_load_status) {
+                        self.is_dirty = true;
+                    }
                     ui.separator();
                     ui.label("Load from image:");
                     ui.horizontal(|ui| {
                         if ui.button("Select Image").clicked() {
-                            self.image_load_status = Some("Opening file dialog...".to_string());
+                            set_image_load_status(
+                                &mut self.image_load_status,
+                                "Opening file dialog...".to_string(),
+                            );
                             if let Some(path) = rfd::FileDialog::new()
                                 .add_filter("Image", &["png", "jpg", "jpeg", "bmp", "gif"])
                                 .pick_file()
                             {
-                                self.image_load_status =
-                                    Some(format!("Loading {:?}...", path.file_name()));
+                                set_image_load_status(
+                                    &mut self.image_load_status,
+                                    format!("Loading {:?}...", path.file_name()),
+                                );
                                 match image::open(&path) {
                                     Ok(loaded_image) => {
-                                        self.image_load_status = Some(format!(
-                                            "Resizing {}x{} to {}x{}...",
-                                            loaded_image.width(),
-                                            loaded_image.height(),
-                                            *width,
-                                            *height
-                                        ));
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!(
+                                                "Resizing {}x{} to {}x{}...",
+                                                loaded_image.width(),
+                                                loaded_image.height(),
+                                                *width,
+                                                *height
+                                            ),
+                                        );
                                         let resized_image = loaded_image.resize(
                                             *width,
                                             *height,
                                             image::imageops::FilterType::Lanczos3,
                                         );
-                                        *data = zebras::zpl::image_to_zpl_hex(
+                                        *data = image_to_zpl_hex_with(
                                             &resized_image,
-                                            self.graphic_threshold,
+                                            &self.graphic_conversion,
+                                        );
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!(
+                                                "Image loaded! {} chars - rendering...",
+                                                data.len()
+                                            ),
                                         );
-                                        self.image_load_status = Some(format!(
-                                            "Image loaded! {} chars - rendering...",
-                                            data.len()
-                                        ));
                                         self.needs_render_after_image = true;
                                     }
                                     Err(e) => {
-                                        self.image_load_status =
-                                            Some(format!("Error loading image: {}", e));
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!("Error loading image: {}", e),
+                                        );
                                     }
                                 }
                             } else {
-                                self.image_load_status = Some("No file selected".to_string());
+                                set_image_load_status(
+                                    &mut self.image_load_status,
+                                    "No file selected".to_string(),
+                                );
                             }
                         }
                     });
-                    ui.horizontal(|ui| {
-                        ui.label("Threshold:");
-                        ui.add(egui::Slider::new(&mut self.graphic_threshold, 0..=255));
-                    });
+                    image_conversion_editor(ui, idx, &mut self.graphic_conversion);
                     ui.label(
                         egui::RichText::new("(Lower = more black, Higher = more white)")
                             .small()
@@ -1006,6 +2052,7 @@ impl Zebras {
                 width,
                 height,
                 data,
+                encoding,
             } => {
                 ui.vertical(|ui| {
                     ui.label(
@@ -1036,44 +2083,70 @@ impl Zebras {
                         }
                     });
                     ui.label(format!("Data length: {} chars", data.len()));
+                    graphic_thumbnail(ui, &mut self.graphic_thumbnails, idx, *width, *height, data);
+                    ui.horizontal(|ui| {
+                        if graphic_encoding_selector(ui, idx, encoding) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(format!(
+                            "{} chars encoded",
+                            encode_graphic_data(data, width.div_ceil(8), *encoding).len()
+                        ));
+                    });
+                    if graphic_file_buttons(ui, width, height, data, &mut self.image_load_status) {
+                        self.is_dirty = true;
+                    }
                     ui.separator();
                     ui.label("Load from image:");
                     ui.horizontal(|ui| {
                         if ui.button("Select Image").clicked() {
-                            self.image_load_status = Some("Opening file dialog...".to_string());
+                            set_image_load_status(
+                                &mut self.image_load_status,
+                                "Opening file dialog...".to_string(),
+                            );
                             if let Some(path) = rfd::FileDialog::new()
                                 .add_filter("Image", &["png", "jpg", "jpeg", "bmp", "gif"])
                                 .pick_file()
                             {
-                                self.image_load_status =
-                                    Some(format!("Loading {:?}...", path.file_name()));
+                                set_image_load_status(
+                                    &mut self.image_load_status,
+                                    format!("Loading {:?}...", path.file_name()),
+                                );
                                 match image::open(&path) {
                                     Ok(loaded_image) => {
-                                        self.image_load_status = Some(format!(
-                                            "Resizing {}x{} to {}x{}...",
-                                            loaded_image.width(),
-                                            loaded_image.height(),
-                                            *width,
-                                            *height
-                                        ));
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!(
+                                                "Resizing {}x{} to {}x{}...",
+                                                loaded_image.width(),
+                                                loaded_image.height(),
+                                                *width,
+                                                *height
+                                            ),
+                                        );
                                         let resized_image = loaded_image.resize(
                                             *width,
                                             *height,
                                             image::imageops::FilterType::Lanczos3,
                                         );
-                                        *data = zebras::zpl::image_to_zpl_hex(
+                                        *data = image_to_zpl_hex_with(
                                             &resized_image,
-                                            self.graphic_threshold,
+                                            &self.graphic_conversion,
+                                        );
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!(
+                                                "Image loaded! {} chars - rendering...",
+                                                data.len()
+                                            ),
                                         );
-                                        self.image_load_status = Some(format!(
-                                            "Image loaded! {} chars - rendering...",
-                                            data.len()
-                                        ));
                                         self.needs_render_after_image = true;
                                     }
                                     Err(e) => {
-                                        self.image_load_status =
-                                            Some(format!("Error loading image: {}", e));
+                                        set_image_load_status(
+                                            &mut self.image_load_status,
+                                            format!("Error loading image: {}", e),
+                                        );
                                     }
                                 }
                             } else {
@@ -1081,10 +2154,7 @@ impl Zebras {
                             }
                         }
                     });
-                    ui.horizontal(|ui| {
-                        ui.label("Threshold:");
-                        ui.add(egui::Slider::new(&mut self.graphic_threshold, 0..=255));
-                    });
+                    image_conversion_editor(ui, idx, &mut self.graphic_conversion);
                     ui.label(
                         egui::RichText::new("(Lower = more black, Higher = more white)")
                             .small()
@@ -1133,13 +2203,19 @@ impl Zebras {
                             self.is_dirty = true;
                         }
                         ui.label("Max Lines:");
-                        if ui.add(egui::DragValue::new(max_lines).speed(1)).lost_focus() {
+                        if ui
+                            .add(egui::DragValue::new(max_lines).speed(1))
+                            .lost_focus()
+                        {
                             self.is_dirty = true;
                         }
                     });
                     ui.horizontal(|ui| {
                         ui.label("Line Spacing:");
-                        if ui.add(egui::DragValue::new(line_spacing).speed(1)).lost_focus() {
+                        if ui
+                            .add(egui::DragValue::new(line_spacing).speed(1))
+                            .lost_focus()
+                        {
                             self.is_dirty = true;
                         }
                         ui.label("Justify:");
@@ -1168,6 +2244,440 @@ impl Zebras {
                     });
                 });
             }
+            ZplCommand::BarcodeFieldDefault {
+                width,
+                ratio,
+                height,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Module Width:");
+                    if ui
+                        .add(egui::DragValue::new(width).speed(1).range(1..=10))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Ratio:");
+                    if ui
+                        .add(egui::DragValue::new(ratio).speed(0.1).range(2.0..=3.0))
+                        .lost_focus()
+                    {
+                        self.is_dirty = true;
+                    }
+                    ui.label("Height:");
+                    if ui.add(egui::DragValue::new(height).speed(1)).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::Code128Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+                mode,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            Some((check_digit, "Check Digit")),
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.horizontal(|ui| {
+                            ui.label("Mode:");
+                            for (value, label) in [
+                                (Code128Mode::NoSelection, "None"),
+                                (Code128Mode::UccCase, "UCC Case"),
+                                (Code128Mode::Automatic, "Auto"),
+                                (Code128Mode::UccEan, "UCC/EAN"),
+                            ] {
+                                if ui.radio_value(mode, value, label).changed() {
+                                    self.is_dirty = true;
+                                }
+                            }
+                        });
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::Code39Barcode {
+                orientation,
+                check_digit,
+                height,
+                print_interpretation,
+                print_above,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            Some((check_digit, "Mod 43 Check Digit")),
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::Ean13Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            None,
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::UpcABarcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                print_check_digit,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            Some((print_check_digit, "Print Check Digit")),
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::Interleaved2of5Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            Some((check_digit, "Mod 10 Check Digit")),
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::Ean8Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        if linear_barcode_editor(
+                            ui,
+                            orientation,
+                            height,
+                            print_interpretation,
+                            print_above,
+                            None,
+                        ) {
+                            self.is_dirty = true;
+                        }
+                        ui.label(
+                            egui::RichText::new(
+                                "Note: Add Field Origin (^FO) before and Field Data (^FD) after this",
+                            )
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::QrCode {
+                model,
+                magnification,
+                error_correction,
+                mask,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Model:");
+                            for value in [1, 2] {
+                                if ui
+                                    .radio_value(model, value, value.to_string())
+                                    .changed()
+                                {
+                                    self.is_dirty = true;
+                                }
+                            }
+                            ui.label("Magnification:");
+                            if ui
+                                .add(egui::DragValue::new(magnification).speed(1).range(1..=10))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            ui.label("Mask:");
+                            if ui
+                                .add(egui::DragValue::new(mask).speed(1).range(0..=7))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Error Correction:");
+                            for (value, label) in [
+                                (QrErrorCorrection::UltraHigh, "H"),
+                                (QrErrorCorrection::High, "Q"),
+                                (QrErrorCorrection::Standard, "M"),
+                                (QrErrorCorrection::HighDensity, "L"),
+                            ] {
+                                if ui.radio_value(error_correction, value, label).changed() {
+                                    self.is_dirty = true;
+                                }
+                            }
+                        });
+                        ui.label(
+                            egui::RichText::new(format!(
+                                "Note: Field Data must start with {}A, (automatic) or {}M, followed by N/A/B/K (manual)",
+                                error_correction, error_correction
+                            ))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    });
+                });
+            }
+            ZplCommand::DataMatrix {
+                orientation,
+                height,
+                quality,
+                columns,
+                rows,
+                format_id,
+                escape,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Module Height:");
+                            if ui
+                                .add(egui::DragValue::new(height).speed(1).range(1..=32000))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            if orientation_selector(ui, orientation) {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Quality:");
+                            for value in [0, 50, 80, 100, 140, 200] {
+                                if ui.radio_value(quality, value, value.to_string()).changed() {
+                                    self.is_dirty = true;
+                                }
+                            }
+                        });
+                        let symbol_range = if *quality == 200 { 10..=144 } else { 9..=49 };
+                        ui.horizontal(|ui| {
+                            if optional_value_editor(ui, "Columns:", columns, symbol_range.clone())
+                            {
+                                self.is_dirty = true;
+                            }
+                            if optional_value_editor(ui, "Rows:", rows, symbol_range) {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.add_enabled_ui(*quality != 200, |ui| {
+                                ui.label("Format ID:");
+                                if ui
+                                    .add(egui::DragValue::new(format_id).speed(1).range(0..=6))
+                                    .lost_focus()
+                                {
+                                    self.is_dirty = true;
+                                }
+                            });
+                            ui.label("Escape Char:");
+                            let mut escape_text = escape.to_string();
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut escape_text)
+                                        .desired_width(20.0),
+                                )
+                                .changed()
+                                && let Some(character) = escape_text.chars().last()
+                            {
+                                *escape = character;
+                                self.is_dirty = true;
+                            }
+                        });
+                    });
+                });
+            }
+            ZplCommand::Pdf417 {
+                orientation,
+                row_height,
+                security_level,
+                columns,
+                rows,
+                truncate,
+            } => {
+                ui.push_id(idx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Row Height:");
+                            if ui
+                                .add(egui::DragValue::new(row_height).speed(1).range(1..=32000))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            if orientation_selector(ui, orientation) {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Security Level:");
+                            if ui
+                                .add(egui::DragValue::new(security_level).speed(1).range(0..=8))
+                                .lost_focus()
+                            {
+                                self.is_dirty = true;
+                            }
+                            if ui.checkbox(truncate, "Truncate").changed() {
+                                self.is_dirty = true;
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            if optional_value_editor(ui, "Columns:", columns, 1..=30) {
+                                self.is_dirty = true;
+                            }
+                            if optional_value_editor(ui, "Rows:", rows, 3..=90) {
+                                self.is_dirty = true;
+                            }
+                        });
+                        if let (Some(columns), Some(rows)) = (*columns, *rows)
+                            && columns * rows > 928
+                        {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("Columns x rows must not exceed 928 ({})", columns * rows),
+                            );
+                        }
+                    });
+                });
+            }
+            ZplCommand::Comment { text } => {
+                ui.horizontal(|ui| {
+                    ui.label("Comment:");
+                    if ui.text_edit_singleline(text).lost_focus() {
+                        self.is_dirty = true;
+                    }
+                });
+            }
+            ZplCommand::Raw {
+                prefix,
+                code,
+                params,
+            } => {
+                ui.vertical(|ui| {
+                    ui.label(
+                        egui::RichText::new("Passed through to the printer unchanged")
+                            .small()
+                            .color(egui::Color32::GRAY),
+                    );
+                    ui.horizontal(|ui| {
+                        ui.label("Prefix:");
+                        if ui.radio_value(prefix, ZplPrefix::Caret, "^").changed() {
+                            self.is_dirty = true;
+                        }
+                        if ui.radio_value(prefix, ZplPrefix::Tilde, "~").changed() {
+                            self.is_dirty = true;
+                        }
+                        ui.label("Code:");
+                        if ui
+                            .add(egui::TextEdit::singleline(code).desired_width(30.0))
+                            .lost_focus()
+                        {
+                            *code = code.to_uppercase();
+                            self.is_dirty = true;
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Params:");
+                        if ui.text_edit_singleline(params).lost_focus() {
+                            self.is_dirty = true;
+                        }
+                    });
+                });
+            }
             _ => {
                 ui.label("(Complex command - not yet editable)");
             }
@@ -1178,13 +2688,7 @@ impl Zebras {
         let image = image::load_from_memory(&image_data);
         match image {
             Ok(img) => {
-                let size = [img.width() as _, img.height() as _];
-                let rgba = img.to_rgba8();
-                let pixels = rgba.as_flat_samples();
-                let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
-                let texture =
-                    ctx.load_texture("zpl_render", color_image, egui::TextureOptions::LINEAR);
-                self.rendered_image = Some(texture);
+                self.show_rendered_image(img, ctx);
                 self.is_loading = false;
             }
             Err(e) => {
@@ -1193,6 +2697,15 @@ impl Zebras {
             }
         }
     }
+
+    fn show_rendered_image(&mut self, img: image::DynamicImage, ctx: &egui::Context) {
+        let size = [img.width() as _, img.height() as _];
+        let rgba = img.to_rgba8();
+        let pixels = rgba.as_flat_samples();
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
+        let texture = ctx.load_texture("zpl_render", color_image, egui::TextureOptions::LINEAR);
+        self.rendered_image = Some(texture);
+    }
 }
 
 impl eframe::App for Zebras {
@@ -1443,23 +2956,74 @@ impl eframe::App for Zebras {
                     if !self.csv_orders.is_empty() {
                         self.render_zpl(ctx);
                     }
-                }
-
-                if !self.csv_orders.is_empty() {
-                    ui.label(format!("{}/{}", self.current_order_index + 1, self.csv_orders.len()));
-
+                }
+
+                if !self.csv_orders.is_empty() {
+                    ui.label(format!(
+                        "{}/{}",
+                        self.current_order_index + 1,
+                        self.csv_orders.len()
+                    ));
+
+                    if ui.button("◀ Prev").clicked() {
+                        self.previous_order();
+                        self.render_zpl(ctx);
+                    }
+
+                    if ui.button("Next ▶").clicked() {
+                        self.next_order();
+                        self.render_zpl(ctx);
+                    }
+
+                    if ui.button("Print All CSV").clicked() {
+                        self.print_all_csv_orders();
+                    }
+
+                    ui.checkbox(&mut self.use_stored_format, "Stored Format")
+                        .on_hover_text(
+                            "Download the label once with ^DF and send only ^FN data per order",
+                        );
+                    if self.use_stored_format {
+                        ui.checkbox(&mut self.format_on_printer, "Already on Printer")
+                            .on_hover_text("Skip the ^DF download and recall the format directly");
+                    }
+                }
+
+                ui.separator();
+
+                if self.data_records.is_empty() {
+                    if ui
+                        .button("Load Data")
+                        .on_hover_text(
+                            "Fill {{placeholders}} in field data from a CSV or JSON file",
+                        )
+                        .clicked()
+                    {
+                        self.load_data_records();
+                        self.render_zpl(ctx);
+                    }
+                } else {
+                    ui.label(format!(
+                        "Record {}/{}",
+                        self.current_record_index + 1,
+                        self.data_records.len()
+                    ));
                     if ui.button("◀ Prev").clicked() {
-                        self.previous_order();
+                        let count = self.data_records.len();
+                        self.load_record_at_index((self.current_record_index + count - 1) % count);
                         self.render_zpl(ctx);
                     }
-
                     if ui.button("Next ▶").clicked() {
-                        self.next_order();
+                        let count = self.data_records.len();
+                        self.load_record_at_index((self.current_record_index + 1) % count);
                         self.render_zpl(ctx);
                     }
-
-                    if ui.button("Print All CSV").clicked() {
-                        self.print_all_csv_orders();
+                    if ui.button("Print All Records").clicked() {
+                        self.print_all_records();
+                    }
+                    if ui.button("Edit Template").clicked() {
+                        self.close_data_records();
+                        self.render_zpl(ctx);
                     }
                 }
 
@@ -1495,6 +3059,41 @@ impl eframe::App for Zebras {
                     ui.spinner();
                 }
 
+                ui.label("Preview:");
+                let previous_renderer = self.preview_renderer;
+                egui::ComboBox::from_id_salt("preview_renderer")
+                    .selected_text(self.preview_renderer.label())
+                    .show_ui(ui, |ui| {
+                        for renderer in [PreviewRenderer::Local, PreviewRenderer::Labelary] {
+                            ui.selectable_value(
+                                &mut self.preview_renderer,
+                                renderer,
+                                renderer.label(),
+                            );
+                        }
+                    });
+                if self.preview_renderer != previous_renderer {
+                    self.render_zpl(ctx);
+                }
+
+                if self.preview_renderer == PreviewRenderer::Local
+                    && self.zpl_commands.iter().any(ZplCommand::is_serialized)
+                {
+                    ui.label("Label #");
+                    let mut label_number = self.preview_label_index + 1;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut label_number)
+                                .speed(1)
+                                .range(1..=99_999_999),
+                        )
+                        .changed()
+                    {
+                        self.preview_label_index = label_number - 1;
+                        self.render_zpl(ctx);
+                    }
+                }
+
                 ui.separator();
 
                 ui.horizontal(|ui| {
@@ -1531,14 +3130,29 @@ impl eframe::App for Zebras {
                                             .clicked()
                                         {
                                             self.selected_printer = Some(idx);
+                                            self.format_on_printer = false;
                                         }
                                     }
                                 });
                         });
 
+                    if let Some(idx) = self.selected_printer
+                        && let Some(printer) = self.printers.get_mut(idx)
+                    {
+                        egui::CollapsingHeader::new("Print Profile")
+                            .id_salt("print_profile")
+                            .show(ui, |ui| {
+                                print_profile_editor(ui, &mut printer.profile);
+                            });
+                    }
+
                     ui.horizontal(|ui| {
                         ui.label("Copies:");
-                        ui.add(egui::DragValue::new(&mut self.print_copies).speed(1).range(1..=100));
+                        ui.add(
+                            egui::DragValue::new(&mut self.print_copies)
+                                .speed(1)
+                                .range(1..=100),
+                        );
                     });
 
                     if ui
@@ -1556,6 +3170,19 @@ impl eframe::App for Zebras {
                     if ui.button("Query Printer...").clicked() {
                         self.show_query_window = true;
                     }
+
+                    if ui
+                        .add_enabled(
+                            self.selected_printer.is_some(),
+                            egui::Button::new("Sync Clock"),
+                        )
+                        .on_hover_text(
+                            "Set the printer's real-time clock (^ST) to this computer's time",
+                        )
+                        .clicked()
+                    {
+                        self.sync_printer_clock();
+                    }
                 }
 
                 if let Some(ref status) = self.print_status {
@@ -1647,7 +3274,9 @@ impl eframe::App for Zebras {
                             ui.separator();
                             if ui.checkbox(&mut self.raw_zpl_mode, "Raw ZPL Mode").changed() {
                                 if self.raw_zpl_mode {
-                                    self.raw_zpl_input = self.get_zpl_text();
+                                    self.raw_zpl_input = commands_to_zpl(&self.zpl_commands);
+                                } else if !self.load_raw_zpl_into_editor() {
+                                    self.raw_zpl_mode = true;
                                 }
                             }
                             if !self.raw_zpl_mode {
@@ -1660,8 +3289,14 @@ impl eframe::App for Zebras {
                         ui.separator();
 
                         if self.raw_zpl_mode {
+                            let diagnostics = lint_zpl(&self.raw_zpl_input);
+                            let diagnostic_rows = match &diagnostics {
+                                Ok(diagnostics) => diagnostics.len().min(MAX_DIAGNOSTIC_ROWS + 1),
+                                Err(_) => 1,
+                            };
                             ui.label("Enter raw ZPL code below:");
-                            let available_height = ui.available_height() - 50.0;
+                            let available_height =
+                                ui.available_height() - 50.0 - diagnostic_rows as f32 * 18.0;
                             egui::ScrollArea::vertical()
                                 .auto_shrink([false, false])
                                 .max_height(available_height)
@@ -1680,15 +3315,19 @@ impl eframe::App for Zebras {
                                     self.raw_zpl_input.clear();
                                     self.is_dirty = true;
                                 }
+                                if ui.button("Load into Editor").clicked()
+                                    && self.load_raw_zpl_into_editor()
+                                {
+                                    self.raw_zpl_mode = false;
+                                }
                                 if ui.button("Copy to Clipboard").clicked() {
                                     #[cfg(not(target_arch = "wasm32"))]
                                     {
                                         use arboard::Clipboard;
-                                        if let Ok(mut clipboard) = Clipboard::new() {
-                                            if let Err(_) = clipboard.set_text(&self.raw_zpl_input) {
+                                        if let Ok(mut clipboard) = Clipboard::new()
+                                            && clipboard.set_text(&self.raw_zpl_input).is_err() {
                                                 self.print_status = Some("Failed to copy to clipboard".to_string());
                                             }
-                                        }
                                     }
                                     #[cfg(target_arch = "wasm32")]
                                     {
@@ -1714,22 +3353,20 @@ impl eframe::App for Zebras {
                                             });
                                         selected
                                     });
-                                if let Some(inner) = example_response.inner {
-                                    if let Some(example) = inner {
+                                if let Some(inner) = example_response.inner
+                                    && let Some(example) = inner {
                                         self.raw_zpl_input = example.to_string();
                                         self.is_dirty = true;
                                     }
-                                }
                                 if ui.button("Paste from Clipboard").clicked() {
                                     #[cfg(not(target_arch = "wasm32"))]
                                     {
                                         use arboard::Clipboard;
-                                        if let Ok(mut clipboard) = Clipboard::new() {
-                                            if let Ok(text) = clipboard.get_text() {
+                                        if let Ok(mut clipboard) = Clipboard::new()
+                                            && let Ok(text) = clipboard.get_text() {
                                                 self.raw_zpl_input = text;
                                                 self.is_dirty = true;
                                             }
-                                        }
                                     }
                                     #[cfg(target_arch = "wasm32")]
                                     {
@@ -1739,6 +3376,17 @@ impl eframe::App for Zebras {
                                     }
                                 }
                             });
+                            match &diagnostics {
+                                Ok(diagnostics) => {
+                                    diagnostic_list(ui, diagnostics, &self.raw_zpl_input)
+                                }
+                                Err(error) => {
+                                    ui.label(
+                                        egui::RichText::new(format!("Parse error: {}", error))
+                                            .color(egui::Color32::RED),
+                                    );
+                                }
+                            }
                         } else if self.show_raw_text {
                             ui.horizontal(|ui| {
                                 ui.label("Generated ZPL:");
@@ -1747,11 +3395,10 @@ impl eframe::App for Zebras {
                                     #[cfg(not(target_arch = "wasm32"))]
                                     {
                                         use arboard::Clipboard;
-                                        if let Ok(mut clipboard) = Clipboard::new() {
-                                            if let Err(_) = clipboard.set_text(&zpl_text) {
+                                        if let Ok(mut clipboard) = Clipboard::new()
+                                            && clipboard.set_text(&zpl_text).is_err() {
                                                 self.print_status = Some("Failed to copy to clipboard".to_string());
                                             }
-                                        }
                                     }
                                     #[cfg(target_arch = "wasm32")]
                                     {
@@ -1790,6 +3437,7 @@ impl eframe::App for Zebras {
                                 }
                                 if ui.button("Font").clicked() {
                                     self.zpl_commands.push(ZplCommand::Font {
+                                        font: FontName::default(),
                                         orientation: FontOrientation::Normal,
                                         height: 30,
                                         width: 30,
@@ -1808,11 +3456,43 @@ impl eframe::App for Zebras {
                                     });
                                     self.is_dirty = true;
                                 }
+                                if ui.button("Circle").clicked() {
+                                    self.zpl_commands.push(ZplCommand::GraphicCircle {
+                                        diameter: 100,
+                                        thickness: 1,
+                                        color: None,
+                                    });
+                                    self.is_dirty = true;
+                                }
+                                if ui.button("Ellipse").clicked() {
+                                    self.zpl_commands.push(ZplCommand::GraphicEllipse {
+                                        width: 150,
+                                        height: 100,
+                                        thickness: 1,
+                                        color: None,
+                                    });
+                                    self.is_dirty = true;
+                                }
+                                if ui.button("Diagonal").clicked() {
+                                    self.zpl_commands.push(ZplCommand::GraphicDiagonalLine {
+                                        width: 100,
+                                        height: 100,
+                                        thickness: 3,
+                                        color: None,
+                                        orientation: 'R',
+                                    });
+                                    self.is_dirty = true;
+                                }
+                                if ui.button("Reverse").clicked() {
+                                    self.zpl_commands.push(ZplCommand::FieldReverse);
+                                    self.is_dirty = true;
+                                }
                                 if ui.button("Graphic Field").clicked() {
                                     self.zpl_commands.push(ZplCommand::GraphicField {
                                         width: 32,
                                         height: 32,
                                         data: String::new(),
+                                        encoding: GraphicEncoding::Auto,
                                     });
                                     self.is_dirty = true;
                                 }
@@ -1830,12 +3510,57 @@ impl eframe::App for Zebras {
                                                 if ui.selectable_label(false, "End Format (^XZ)").clicked() {
                                                     selected = Some(ZplCommand::EndFormat);
                                                 }
+                                                if ui.selectable_label(false, "Change Encoding (^CI)").clicked() {
+                                                    selected = Some(ZplCommand::ChangeEncoding { encoding: 28 });
+                                                }
+                                                if ui.selectable_label(false, "Print Width (^PW)").clicked() {
+                                                    selected = Some(ZplCommand::PrintWidth { width: 812 });
+                                                }
+                                                if ui.selectable_label(false, "Label Length (^LL)").clicked() {
+                                                    selected = Some(ZplCommand::LabelLength { length: 1218 });
+                                                }
+                                                if ui.selectable_label(false, "Label Home (^LH)").clicked() {
+                                                    selected = Some(ZplCommand::LabelHome { x: 0, y: 0 });
+                                                }
+                                                if ui.selectable_label(false, "Label Shift (^LS)").clicked() {
+                                                    selected = Some(ZplCommand::LabelShift { shift: 0 });
+                                                }
+                                                if ui.selectable_label(false, "Label Top (^LT)").clicked() {
+                                                    selected = Some(ZplCommand::LabelTop { offset: 0 });
+                                                }
+                                                if ui.selectable_label(false, "Print Orientation (^PO)").clicked() {
+                                                    selected = Some(ZplCommand::PrintOrientation { inverted: false });
+                                                }
+                                                if ui.selectable_label(false, "Print Rate (^PR)").clicked() {
+                                                    selected = Some(ZplCommand::PrintRate {
+                                                        print_speed: 4,
+                                                        slew_speed: None,
+                                                        backfeed_speed: None,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Media Darkness (^MD)").clicked() {
+                                                    selected = Some(ZplCommand::MediaDarkness { adjustment: 0 });
+                                                }
+                                                if ui.selectable_label(false, "Set Darkness (~SD)").clicked() {
+                                                    selected = Some(ZplCommand::SetDarkness { darkness: 15 });
+                                                }
+                                                if ui.selectable_label(false, "Media Type (^MT)").clicked() {
+                                                    selected = Some(ZplCommand::MediaType {
+                                                        media_type: MediaType::DirectThermal,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Media Tracking (^MN)").clicked() {
+                                                    selected = Some(ZplCommand::MediaTracking {
+                                                        tracking: MediaTracking::WebSensing,
+                                                    });
+                                                }
                                                 if ui.selectable_label(false, "Download Graphic (~DG)").clicked() {
                                                     selected = Some(ZplCommand::DownloadGraphic {
                                                         name: "GRAPHIC".to_string(),
                                                         width: 32,
                                                         height: 32,
                                                         data: String::new(),
+                                                        encoding: GraphicEncoding::Auto,
                                                     });
                                                 }
                                                 if ui.selectable_label(false, "Recall Graphic (^XG)").clicked() {
@@ -1859,28 +3584,193 @@ impl eframe::App for Zebras {
                                                         print_interpretation: true,
                                                         print_above: false,
                                                         check_digit: false,
-                                                        mode: FieldOrientation::Normal,
+                                                        mode: Code128Mode::NoSelection,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Code 39 Barcode (^B3)").clicked() {
+                                                    selected = Some(ZplCommand::Code39Barcode {
+                                                        orientation: FieldOrientation::Normal,
+                                                        check_digit: false,
+                                                        height: 80,
+                                                        print_interpretation: true,
+                                                        print_above: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "EAN-13 Barcode (^BE)").clicked() {
+                                                    selected = Some(ZplCommand::Ean13Barcode {
+                                                        orientation: FieldOrientation::Normal,
+                                                        height: 80,
+                                                        print_interpretation: true,
+                                                        print_above: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "UPC-A Barcode (^BU)").clicked() {
+                                                    selected = Some(ZplCommand::UpcABarcode {
+                                                        orientation: FieldOrientation::Normal,
+                                                        height: 80,
+                                                        print_interpretation: true,
+                                                        print_above: false,
+                                                        print_check_digit: true,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Interleaved 2 of 5 (^B2)").clicked() {
+                                                    selected = Some(ZplCommand::Interleaved2of5Barcode {
+                                                        orientation: FieldOrientation::Normal,
+                                                        height: 80,
+                                                        print_interpretation: true,
+                                                        print_above: false,
+                                                        check_digit: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "EAN-8 Barcode (^B8)").clicked() {
+                                                    selected = Some(ZplCommand::Ean8Barcode {
+                                                        orientation: FieldOrientation::Normal,
+                                                        height: 80,
+                                                        print_interpretation: true,
+                                                        print_above: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "QR Code (^BQ)").clicked() {
+                                                    selected = Some(ZplCommand::QrCode {
+                                                        model: 2,
+                                                        magnification: 5,
+                                                        error_correction: QrErrorCorrection::High,
+                                                        mask: 7,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "DataMatrix (^BX)").clicked() {
+                                                    selected = Some(ZplCommand::DataMatrix {
+                                                        orientation: FieldOrientation::Normal,
+                                                        height: 6,
+                                                        quality: 200,
+                                                        columns: None,
+                                                        rows: None,
+                                                        format_id: 6,
+                                                        escape: '~',
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "PDF417 (^B7)").clicked() {
+                                                    selected = Some(ZplCommand::Pdf417 {
+                                                        orientation: FieldOrientation::Normal,
+                                                        row_height: 10,
+                                                        security_level: 5,
+                                                        columns: None,
+                                                        rows: None,
+                                                        truncate: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Field Clock (^FC)").clicked() {
+                                                    selected = Some(ZplCommand::FieldClock {
+                                                        primary: '%',
+                                                        secondary: None,
+                                                        tertiary: None,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Clock Mode (^SL)").clicked() {
+                                                    selected = Some(ZplCommand::ClockMode {
+                                                        mode: ClockMode::StartTime,
+                                                        language: None,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Set Time (^ST)").clicked() {
+                                                    selected = Some(zebras::clock::set_time_command(
+                                                        zebras::clock::local_time(),
+                                                    ));
+                                                }
+                                                if ui.selectable_label(false, "Serial Number (^SN)").clicked() {
+                                                    selected = Some(ZplCommand::SerialNumber {
+                                                        start: "001".to_string(),
+                                                        increment: 1,
+                                                        leading_zeros: true,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Serialization Field (^SF)").clicked() {
+                                                    selected = Some(ZplCommand::SerializationField {
+                                                        mask: "DDD".to_string(),
+                                                        increment: "1".to_string(),
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Print Quantity (^PQ)").clicked() {
+                                                    selected = Some(ZplCommand::PrintQuantity {
+                                                        quantity: 1,
+                                                        pause_count: 0,
+                                                        replicates: 0,
+                                                        override_pause: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Download Format (^DF)").clicked() {
+                                                    selected = Some(ZplCommand::DownloadFormat {
+                                                        name: "R:FORMAT.ZPL".to_string(),
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Recall Format (^XF)").clicked() {
+                                                    selected = Some(ZplCommand::RecallFormat {
+                                                        name: "R:FORMAT.ZPL".to_string(),
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Field Number (^FN)").clicked() {
+                                                    selected = Some(ZplCommand::FieldNumber { number: 1 });
+                                                }
+                                                if ui.selectable_label(false, "Media Mode (^MM)").clicked() {
+                                                    selected = Some(ZplCommand::MediaMode {
+                                                        mode: MediaMode::TearOff,
+                                                        prepeel: false,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Cut Now (^CN)").clicked() {
+                                                    selected = Some(ZplCommand::ImmediateCut { cut: true });
+                                                }
+                                                if ui.selectable_label(false, "Delayed Cut (~JK)").clicked() {
+                                                    selected = Some(ZplCommand::DelayedCut);
+                                                }
+                                                if ui.selectable_label(false, "Kiosk Values (^KV)").clicked() {
+                                                    selected = Some(ZplCommand::KioskValues {
+                                                        cut_amount: 0,
+                                                        cut_margin: 9,
+                                                        present_type: KioskPresentType::Eject,
+                                                        present_timeout: 6,
+                                                        loop_length: 400,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Field Typeset (^FT)").clicked() {
+                                                    selected = Some(ZplCommand::FieldTypeset {
+                                                        x: 0,
+                                                        y: 0,
+                                                        justification: None,
+                                                    });
+                                                }
+                                                if ui.selectable_label(false, "Font Alias (^CW)").clicked() {
+                                                    selected = Some(ZplCommand::FontAlias {
+                                                        alias: 'Z',
+                                                        path: "E:ARIAL.TTF".to_string(),
                                                     });
                                                 }
-                                                if ui.selectable_label(false, "Media Mode Delayed (^MMD)").clicked() {
-                                                    selected = Some(ZplCommand::MediaModeDelayed);
+                                                if ui.selectable_label(false, "Download Font (~DY)").clicked() {
+                                                    selected = Some(ZplCommand::DownloadFont {
+                                                        path: "E:ARIAL.TTF".to_string(),
+                                                        method: FontDownload::TrueType,
+                                                        data: String::new(),
+                                                    });
                                                 }
-                                                if ui.selectable_label(false, "Media Mode Tear-off (^MMT)").clicked() {
-                                                    selected = Some(ZplCommand::MediaModeTearOff);
+                                                if ui.selectable_label(false, "Comment (^FX)").clicked() {
+                                                    selected = Some(ZplCommand::Comment { text: String::new() });
                                                 }
-                                                if ui.selectable_label(false, "Cut Now (~JK)").clicked() {
-                                                    selected = Some(ZplCommand::CutNow);
+                                                if ui.selectable_label(false, "Raw Command").clicked() {
+                                                    selected = Some(ZplCommand::Raw {
+                                                        prefix: ZplPrefix::Caret,
+                                                        code: String::new(),
+                                                        params: String::new(),
+                                                    });
                                                 }
                                             });
                                         selected
                                     });
 
-                                if let Some(inner) = response.inner {
-                                    if let Some(command) = inner {
+                                if let Some(inner) = response.inner
+                                    && let Some(command) = inner {
                                         self.zpl_commands.push(command);
                                         self.is_dirty = true;
                                     }
-                                }
                             });
 
                             ui.separator();
@@ -1894,6 +3784,8 @@ impl eframe::App for Zebras {
                                     let mut to_move_up = None;
                                     let mut to_move_down = None;
                                     let command_count = self.zpl_commands.len();
+                                    let diagnostics = lint_commands(&self.zpl_commands);
+                                    diagnostic_summary(ui, &diagnostics);
 
                                     for idx in 0..command_count {
                                         ui.group(|ui| {
@@ -1907,13 +3799,28 @@ impl eframe::App for Zebras {
                                                 ui.vertical(|ui| {
                                                     egui::CollapsingHeader::new(
                                                         egui::RichText::new(
-                                                            self.zpl_commands[idx].command_name()
+                                                            command_title(&self.zpl_commands[idx])
                                                         ).strong()
                                                     )
+                                                    .id_salt(("command", idx))
                                                     .default_open(false)
                                                     .show(ui, |ui| {
                                                         self.render_command_editor(ui, idx);
                                                     });
+                                                    for diagnostic in diagnostics
+                                                        .iter()
+                                                        .filter(|diagnostic| diagnostic.index == idx)
+                                                    {
+                                                        ui.label(
+                                                            egui::RichText::new(format!(
+                                                                "{}: {}",
+                                                                diagnostic.severity.label(),
+                                                                diagnostic.message
+                                                            ))
+                                                            .small()
+                                                            .color(severity_color(diagnostic.severity)),
+                                                        );
+                                                    }
                                                 });
 
                                                 ui.vertical(|ui| {
@@ -1975,8 +3882,7 @@ impl eframe::App for Zebras {
 
                                     let scale = (max_width / size.x)
                                         .min(max_height / size.y)
-                                        .min(2.0)
-                                        .max(0.5);
+                                        .clamp(0.5, 2.0);
                                     let display_size = size * scale;
 
                                     ui.centered_and_justified(|ui| {
@@ -2464,3 +4370,472 @@ impl eframe::App for Zebras {
         }
     }
 }
+
+fn orientation_selector(ui: &mut egui::Ui, orientation: &mut FieldOrientation) -> bool {
+    ui.label("Orientation:");
+    let mut changed = false;
+    for (value, label) in [
+        (FieldOrientation::Normal, "N"),
+        (FieldOrientation::Rotated90, "R"),
+        (FieldOrientation::Rotated180, "I"),
+        (FieldOrientation::Rotated270, "B"),
+    ] {
+        changed |= ui.radio_value(orientation, value, label).changed();
+    }
+    changed
+}
+
+fn optional_value_editor(
+    ui: &mut egui::Ui,
+    label: &str,
+    value: &mut Option<u32>,
+    range: std::ops::RangeInclusive<u32>,
+) -> bool {
+    ui.label(label);
+    let mut automatic = value.is_none();
+    let mut changed = false;
+    if ui.checkbox(&mut automatic, "Auto").changed() {
+        *value = if automatic {
+            None
+        } else {
+            Some(*range.start())
+        };
+        changed = true;
+    }
+    if let Some(number) = value {
+        *number = (*number).clamp(*range.start(), *range.end());
+        changed |= ui
+            .add(egui::DragValue::new(number).speed(1).range(range))
+            .lost_focus();
+    }
+    changed
+}
+
+fn linear_barcode_editor(
+    ui: &mut egui::Ui,
+    orientation: &mut FieldOrientation,
+    height: &mut u32,
+    print_interpretation: &mut bool,
+    print_above: &mut bool,
+    extra_option: Option<(&mut bool, &str)>,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Height:");
+        changed |= ui.add(egui::DragValue::new(height).speed(1)).lost_focus();
+        changed |= orientation_selector(ui, orientation);
+    });
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(print_interpretation, "Interpretation")
+            .changed();
+        changed |= ui.checkbox(print_above, "Above").changed();
+        if let Some((value, label)) = extra_option {
+            changed |= ui.checkbox(value, label).changed();
+        }
+    });
+    changed
+}
+
+fn print_profile_editor(ui: &mut egui::Ui, profile: &mut PrintProfile) {
+    ui.horizontal(|ui| {
+        ui.label("Resolution:");
+        egui::ComboBox::from_id_salt("print_profile_resolution")
+            .selected_text(profile.resolution.label())
+            .show_ui(ui, |ui| {
+                for value in Resolution::all() {
+                    ui.selectable_value(&mut profile.resolution, value, value.label());
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Media Mode:");
+        media_mode_selector(ui, "print_profile_media_mode", &mut profile.media_mode);
+    });
+    ui.horizontal(|ui| {
+        optional_value_editor(ui, "Speed (in/s):", &mut profile.print_speed, 1..=14);
+    });
+    ui.horizontal(|ui| {
+        optional_value_editor(ui, "Darkness:", &mut profile.darkness, 0..=30);
+    });
+    ui.horizontal(|ui| {
+        ui.label("Media:");
+        for (value, label) in [
+            (None, "Printer Default"),
+            (Some(MediaType::DirectThermal), "Direct Thermal"),
+            (Some(MediaType::ThermalTransfer), "Thermal Transfer"),
+        ] {
+            ui.radio_value(&mut profile.media_type, value, label);
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Tracking:");
+        egui::ComboBox::from_id_salt("print_profile_tracking")
+            .selected_text(media_tracking_label(profile.media_tracking))
+            .show_ui(ui, |ui| {
+                for value in [
+                    None,
+                    Some(MediaTracking::WebSensing),
+                    Some(MediaTracking::MarkSensing),
+                    Some(MediaTracking::Continuous),
+                    Some(MediaTracking::ContinuousVariableLength),
+                    Some(MediaTracking::AutoDetect),
+                ] {
+                    ui.selectable_value(
+                        &mut profile.media_tracking,
+                        value,
+                        media_tracking_label(value),
+                    );
+                }
+            });
+    });
+    ui.label(
+        egui::RichText::new(format!("Prefix: {}", profile.to_zpl()))
+            .small()
+            .color(egui::Color32::GRAY),
+    );
+}
+
+fn image_conversion_editor(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    conversion: &mut ImageConversion,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Threshold:");
+        ui.add(egui::Slider::new(&mut conversion.threshold, 0..=255));
+        egui::ComboBox::from_id_salt(("dither_mode", id))
+            .selected_text(conversion.mode.label())
+            .show_ui(ui, |ui| {
+                for mode in DitherMode::all() {
+                    ui.selectable_value(&mut conversion.mode, mode, mode.label());
+                }
+            });
+    });
+    ui.horizontal(|ui| {
+        ui.label("Gamma:");
+        ui.add(egui::Slider::new(&mut conversion.gamma, 0.2..=3.0));
+        ui.checkbox(&mut conversion.invert, "Invert");
+        ui.checkbox(&mut conversion.alpha_as_white, "Transparent = White");
+    });
+}
+
+fn command_title(command: &ZplCommand) -> String {
+    match command {
+        ZplCommand::Raw { prefix, code, .. } => format!("Raw Command ({}{})", prefix, code),
+        ZplCommand::Comment { text } if !text.is_empty() => format!("Comment: {}", text),
+        command => command.command_name().to_string(),
+    }
+}
+
+fn severity_color(severity: Severity) -> egui::Color32 {
+    match severity {
+        Severity::Error => egui::Color32::RED,
+        Severity::Warning => egui::Color32::YELLOW,
+        Severity::Info => egui::Color32::LIGHT_BLUE,
+    }
+}
+
+fn diagnostic_summary(ui: &mut egui::Ui, diagnostics: &[Diagnostic]) {
+    let count = |severity| {
+        diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    };
+    let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+    if errors == 0 && warnings == 0 {
+        return;
+    }
+    let severity = if errors > 0 {
+        Severity::Error
+    } else {
+        Severity::Warning
+    };
+    ui.label(
+        egui::RichText::new(format!("{} error(s), {} warning(s)", errors, warnings))
+            .color(severity_color(severity)),
+    );
+}
+
+fn diagnostic_list(ui: &mut egui::Ui, diagnostics: &[Diagnostic], source: &str) {
+    for diagnostic in diagnostics.iter().take(MAX_DIAGNOSTIC_ROWS) {
+        let location = match diagnostic.offset {
+            Some(offset) => format!(
+                "Line {}",
+                source[..offset.min(source.len())].matches('\n').count() + 1
+            ),
+            _ => format!("#{}", diagnostic.index + 1),
+        };
+        ui.label(
+            egui::RichText::new(format!(
+                "{} {}: {}",
+                location,
+                diagnostic.severity.label(),
+                diagnostic.message
+            ))
+            .small()
+            .color(severity_color(diagnostic.severity)),
+        );
+    }
+    if diagnostics.len() > MAX_DIAGNOSTIC_ROWS {
+        ui.label(
+            egui::RichText::new(format!(
+                "...and {} more",
+                diagnostics.len() - MAX_DIAGNOSTIC_ROWS
+            ))
+            .small()
+            .color(egui::Color32::GRAY),
+        );
+    }
+}
+
+fn graphic_thumbnail(
+    ui: &mut egui::Ui,
+    cache: &mut HashMap<usize, (u64, egui::TextureHandle)>,
+    idx: usize,
+    width: u32,
+    height: u32,
+    data: &str,
+) {
+    if width == 0 || height == 0 || data.is_empty() {
+        cache.remove(&idx);
+        return;
+    }
+
+    let mut hasher = std::hash::DefaultHasher::new();
+    (width, height, data).hash(&mut hasher);
+    let key = hasher.finish();
+
+    let texture = match cache.get(&idx) {
+        Some((cached_key, texture)) if *cached_key == key => texture.clone(),
+        _ => {
+            let image = graphic_to_image(width, height, data).to_rgba8();
+            let color_image = egui::ColorImage::from_rgba_unmultiplied(
+                [width as usize, height as usize],
+                image.as_raw(),
+            );
+            let texture = ui.ctx().load_texture(
+                format!("graphic_thumbnail_{}", idx),
+                color_image,
+                egui::TextureOptions::NEAREST,
+            );
+            cache.insert(idx, (key, texture.clone()));
+            texture
+        }
+    };
+
+    let scale = (GRAPHIC_THUMBNAIL_SIZE / width.max(height) as f32).min(4.0);
+    ui.add(
+        egui::Image::new(&texture)
+            .fit_to_exact_size(egui::vec2(width as f32 * scale, height as f32 * scale)),
+    );
+}
+
+fn set_image_load_status(status: &mut Option<String>, message: String) {
+    println!("{}", message);
+    *status = Some(message);
+}
+
+fn graphic_file_buttons(
+    ui: &mut egui::Ui,
+    width: &mut u32,
+    height: &mut u32,
+    data: &mut String,
+    status: &mut Option<String>,
+) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        if ui.button("Extract from .prn").clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter("ZPL", &["prn", "zpl", "txt"])
+                .pick_file()
+        {
+            let graphic = std::fs::read(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| extract_graphics(&bytes));
+            match graphic {
+                Ok(graphics) => match graphics.into_iter().next() {
+                    Some(
+                        ZplCommand::GraphicField {
+                            width: w,
+                            height: h,
+                            data: d,
+                            ..
+                        }
+                        | ZplCommand::DownloadGraphic {
+                            width: w,
+                            height: h,
+                            data: d,
+                            ..
+                        },
+                    ) => {
+                        *width = w;
+                        *height = h;
+                        *data = d;
+                        *status = Some(format!("Extracted {}x{} graphic", w, h));
+                        changed = true;
+                    }
+                    _ => *status = Some("No graphics found in file".to_string()),
+                },
+                Err(e) => *status = Some(format!("Error extracting graphic: {}", e)),
+            }
+        }
+        if ui.button("Save as PNG").clicked()
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter("PNG", &["png"])
+                .set_file_name("graphic.png")
+                .save_file()
+        {
+            *status = Some(match graphic_to_image(*width, *height, data).save(&path) {
+                Ok(()) => format!("Saved {}", path.display()),
+                Err(e) => format!("Error saving image: {}", e),
+            });
+        }
+    });
+    changed
+}
+
+fn graphic_encoding_selector(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    encoding: &mut GraphicEncoding,
+) -> bool {
+    ui.label("Encoding:");
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(("graphic_encoding", id))
+        .selected_text(encoding.label())
+        .show_ui(ui, |ui| {
+            for value in [
+                GraphicEncoding::Auto,
+                GraphicEncoding::Hex,
+                GraphicEncoding::Acs,
+                GraphicEncoding::Z64,
+                GraphicEncoding::B64,
+            ] {
+                changed |= ui
+                    .selectable_value(encoding, value, value.label())
+                    .changed();
+            }
+        });
+    changed
+}
+
+fn font_selector(ui: &mut egui::Ui, idx: usize, font: &mut FontName) -> bool {
+    let mut changed = false;
+    let bitmap = font.zpl_font();
+    let is_device = matches!(font, FontName::Device(_));
+    let selected_text = match (bitmap, is_device) {
+        (Some(zpl_font), _) => zpl_font.label(),
+        (None, true) => "Device (^A@)",
+        (None, false) => "Alias (^CW)",
+    };
+    egui::ComboBox::from_id_salt(("font", idx))
+        .selected_text(selected_text)
+        .show_ui(ui, |ui| {
+            for zpl_font in ZplFont::all() {
+                if ui
+                    .selectable_label(bitmap == Some(zpl_font), zpl_font.label())
+                    .clicked()
+                {
+                    *font = zpl_font.into();
+                    changed = true;
+                }
+            }
+            let is_alias = bitmap.is_none() && !is_device;
+            if ui.selectable_label(is_alias, "Alias (^CW)").clicked() && !is_alias {
+                *font = FontName::Letter('Z');
+                changed = true;
+            }
+            if ui.selectable_label(is_device, "Device (^A@)").clicked() && !is_device {
+                *font = FontName::Device("E:ARIAL.TTF".to_string());
+                changed = true;
+            }
+        });
+
+    match font {
+        FontName::Letter(letter) if font_letter_is_alias(*letter) => {
+            let mut text = letter.to_string();
+            if ui
+                .add(egui::TextEdit::singleline(&mut text).desired_width(20.0))
+                .changed()
+                && let Some(value) = text.chars().last().filter(|c| is_font_letter(*c))
+            {
+                *letter = value.to_ascii_uppercase();
+                changed = true;
+            }
+        }
+        FontName::Device(path) => {
+            if ui.text_edit_singleline(path).lost_focus() {
+                *path = path.trim().to_uppercase();
+                changed = true;
+            }
+        }
+        FontName::Letter(_) => {}
+    }
+    changed
+}
+
+fn font_letter_is_alias(letter: char) -> bool {
+    FontName::Letter(letter).zpl_font().is_none()
+}
+
+fn skip_invalid_range(value: u32, previous: u32, invalid: RangeInclusive<u32>) -> u32 {
+    if !invalid.contains(&value) {
+        value
+    } else if value > previous {
+        invalid.end() + 1
+    } else {
+        invalid.start().saturating_sub(1)
+    }
+}
+
+fn media_mode_selector(ui: &mut egui::Ui, id: &str, mode: &mut MediaMode) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(format!("{} ({})", mode.label(), mode))
+        .show_ui(ui, |ui| {
+            for value in MediaMode::all() {
+                changed |= ui
+                    .selectable_value(mode, value, format!("{} ({})", value.label(), value))
+                    .changed();
+            }
+        });
+    changed
+}
+
+fn media_tracking_label(tracking: Option<MediaTracking>) -> &'static str {
+    match tracking {
+        None => "Printer Default",
+        Some(MediaTracking::WebSensing) => "Gap/Notch (Y)",
+        Some(MediaTracking::MarkSensing) => "Black Mark (M)",
+        Some(MediaTracking::Continuous) => "Continuous (N)",
+        Some(MediaTracking::ContinuousVariableLength) => "Continuous Variable (V)",
+        Some(MediaTracking::AutoDetect) => "Auto Detect (A)",
+    }
+}
+
+fn line_color_selector(ui: &mut egui::Ui, color: &mut Option<char>) -> bool {
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Color:");
+        let mut color_selection = match color {
+            Some('B') => 0,
+            Some('W') => 1,
+            _ => 2,
+        };
+        let prev_selection = color_selection;
+        ui.radio_value(&mut color_selection, 0, "Black");
+        ui.radio_value(&mut color_selection, 1, "White");
+        ui.radio_value(&mut color_selection, 2, "Default");
+        if color_selection != prev_selection {
+            *color = match color_selection {
+                0 => Some('B'),
+                1 => Some('W'),
+                _ => None,
+            };
+            changed = true;
+        }
+    });
+    changed
+}
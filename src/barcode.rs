@@ -0,0 +1,1368 @@
+use crate::zpl::{Code128Mode, QrErrorCorrection, QrFieldData, QrInputMode, ZplCommand};
+use qrcode::bits::Bits;
+use qrcode::{Color, EcLevel, QrCode, Version};
+use std::fmt;
+
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212",
+    "221213", "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221",
+    "223211", "221132", "221231", "213212", "223112", "312131", "311222", "321122", "321221",
+    "312212", "322112", "322211", "212123", "212321", "232121", "111323", "131123", "131321",
+    "112313", "132113", "132311", "211313", "231113", "231311", "112133", "112331", "132131",
+    "113123", "113321", "133121", "313121", "211331", "231131", "213113", "213311", "213131",
+    "311123", "311321", "331121", "312113", "312311", "332111", "314111", "221411", "431111",
+    "111224", "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111", "111242",
+    "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311",
+    "113141", "114131", "311141", "411131", "211412", "211214", "211232", "2331112",
+];
+
+const CODE39_CHARSET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-. $/+%";
+
+const CODE39_PATTERNS: [&str; 44] = [
+    "nnnwwnwnn",
+    "wnnwnnnnw",
+    "nnwwnnnnw",
+    "wnwwnnnnn",
+    "nnnwwnnnw",
+    "wnnwwnnnn",
+    "nnwwwnnnn",
+    "nnnwnnwnw",
+    "wnnwnnwnn",
+    "nnwwnnwnn",
+    "wnnnnwnnw",
+    "nnwnnwnnw",
+    "wnwnnwnnn",
+    "nnnnwwnnw",
+    "wnnnwwnnn",
+    "nnwnwwnnn",
+    "nnnnnwwnw",
+    "wnnnnwwnn",
+    "nnwnnwwnn",
+    "nnnnwwwnn",
+    "wnnnnnnww",
+    "nnwnnnnww",
+    "wnwnnnnwn",
+    "nnnnwnnww",
+    "wnnnwnnwn",
+    "nnwnwnnwn",
+    "nnnnnnwww",
+    "wnnnnnwwn",
+    "nnwnnnwwn",
+    "nnnnwnwwn",
+    "wwnnnnnnw",
+    "nwwnnnnnw",
+    "wwwnnnnnn",
+    "nwnnwnnnw",
+    "wwnnwnnnn",
+    "nwwnwnnnn",
+    "nwnnnnwnw",
+    "wwnnnnwnn",
+    "nwwnnnwnn",
+    "nwnwnwnnn",
+    "nwnwnnnwn",
+    "nwnnnwnwn",
+    "nnnwnwnwn",
+    "nwnnwnwnn",
+];
+
+const CODE39_START_STOP: usize = 43;
+
+const INTERLEAVED_2OF5_PATTERNS: [&str; 10] = [
+    "nnwwn", "wnnnw", "nwnnw", "wwnnn", "nnwnw", "wnwnn", "nwwnn", "nnnww", "wnnwn", "nwnwn",
+];
+
+const EAN_L_PATTERNS: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011", "0110001", "0101111", "0111011",
+    "0110111", "0001011",
+];
+
+const EAN13_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG", "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL",
+    "LGGLGL",
+];
+
+const DATA_MATRIX_SIZES: [(u32, usize); 24] = [
+    (10, 3),
+    (12, 5),
+    (14, 8),
+    (16, 12),
+    (18, 18),
+    (20, 22),
+    (22, 30),
+    (24, 36),
+    (26, 44),
+    (32, 62),
+    (36, 86),
+    (40, 114),
+    (44, 144),
+    (48, 174),
+    (52, 204),
+    (64, 280),
+    (72, 368),
+    (80, 456),
+    (88, 576),
+    (96, 696),
+    (104, 816),
+    (120, 1050),
+    (132, 1304),
+    (144, 1558),
+];
+
+const DATA_MATRIX_LEGACY_LIMITS: [usize; 7] = [271, 596, 452, 394, 413, 310, 271];
+
+const PDF417_MAX_CODEWORDS: usize = 928;
+
+const CODE128_SHIFT: u8 = 98;
+const CODE128_CODE_C: u8 = 99;
+const CODE128_CODE_B: u8 = 100;
+const CODE128_CODE_A: u8 = 101;
+const CODE128_FNC1: u8 = 102;
+const CODE128_START_A: u8 = 103;
+const CODE128_START_B: u8 = 104;
+const CODE128_START_C: u8 = 105;
+const CODE128_STOP: u8 = 106;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BarcodeDataError {
+    Empty,
+    InvalidCharacter {
+        position: usize,
+        character: char,
+    },
+    InvalidLength {
+        expected: &'static str,
+        actual: usize,
+    },
+    CheckDigitMismatch {
+        expected: char,
+        actual: char,
+    },
+    Unencodable(String),
+}
+
+impl fmt::Display for BarcodeDataError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BarcodeDataError::Empty => write!(f, "Barcode data is empty"),
+            BarcodeDataError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "Invalid character '{}' at position {}",
+                character,
+                position + 1
+            ),
+            BarcodeDataError::InvalidLength { expected, actual } => {
+                write!(f, "Expected {}, got {} characters", expected, actual)
+            }
+            BarcodeDataError::CheckDigitMismatch { expected, actual } => write!(
+                f,
+                "Check digit mismatch: expected {}, got {}",
+                expected, actual
+            ),
+            BarcodeDataError::Unencodable(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for BarcodeDataError {}
+
+impl From<BarcodeDataError> for String {
+    fn from(error: BarcodeDataError) -> Self {
+        error.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearSymbol {
+    pub elements: Vec<u32>,
+    pub text: String,
+}
+
+impl LinearSymbol {
+    pub fn width(&self) -> u32 {
+        self.elements.iter().sum()
+    }
+
+    pub fn modules(&self) -> Vec<bool> {
+        self.elements
+            .iter()
+            .enumerate()
+            .flat_map(|(index, width)| std::iter::repeat_n(index % 2 == 0, *width as usize))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixSymbol {
+    pub columns: u32,
+    pub rows: u32,
+    pub modules: Vec<bool>,
+}
+
+impl MatrixSymbol {
+    pub fn is_dark(&self, column: u32, row: u32) -> bool {
+        column < self.columns
+            && row < self.rows
+            && self.modules[(row * self.columns + column) as usize]
+    }
+}
+
+pub fn mod43_check_digit(data: &str) -> Option<char> {
+    if data.is_empty() {
+        return None;
+    }
+    let sum = data
+        .chars()
+        .map(|character| CODE39_CHARSET.find(character))
+        .sum::<Option<usize>>()?;
+    CODE39_CHARSET.chars().nth(sum % 43)
+}
+
+pub fn validate_code39(data: &str) -> Result<(), BarcodeDataError> {
+    if data.is_empty() {
+        return Err(BarcodeDataError::Empty);
+    }
+    match data
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !CODE39_CHARSET.contains(*character))
+    {
+        Some((position, character)) => Err(BarcodeDataError::InvalidCharacter {
+            position,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn validate_digits(data: &str) -> Result<(), BarcodeDataError> {
+    if data.is_empty() {
+        return Err(BarcodeDataError::Empty);
+    }
+    match data
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !character.is_ascii_digit())
+    {
+        Some((position, character)) => Err(BarcodeDataError::InvalidCharacter {
+            position,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn digits_with_check_digit(
+    data: &str,
+    payload_length: usize,
+    expected: &'static str,
+) -> Result<String, BarcodeDataError> {
+    validate_digits(data)?;
+    let payload = &data[..payload_length.min(data.len())];
+    let check = (b'0' + mod10_check_digit(payload).unwrap_or(0)) as char;
+
+    if data.len() == payload_length {
+        Ok(format!("{}{}", data, check))
+    } else if data.len() == payload_length + 1 {
+        let actual = data.chars().last().unwrap_or_default();
+        if actual != check {
+            return Err(BarcodeDataError::CheckDigitMismatch {
+                expected: check,
+                actual,
+            });
+        }
+        Ok(data.to_string())
+    } else {
+        Err(BarcodeDataError::InvalidLength {
+            expected,
+            actual: data.len(),
+        })
+    }
+}
+
+pub fn validate_ean13(data: &str) -> Result<String, BarcodeDataError> {
+    digits_with_check_digit(data, 12, "12 or 13 digits")
+}
+
+pub fn validate_upc_a(data: &str) -> Result<String, BarcodeDataError> {
+    digits_with_check_digit(data, 11, "11 or 12 digits")
+}
+
+pub fn validate_ean8(data: &str) -> Result<String, BarcodeDataError> {
+    digits_with_check_digit(data, 7, "7 or 8 digits")
+}
+
+pub fn validate_interleaved_2of5(
+    data: &str,
+    check_digit: bool,
+) -> Result<String, BarcodeDataError> {
+    validate_digits(data)?;
+    let mut digits = data.to_string();
+    if check_digit {
+        digits.push((b'0' + mod10_check_digit(data).unwrap_or(0)) as char);
+    }
+    if digits.len() % 2 == 1 {
+        digits.insert(0, '0');
+    }
+    Ok(digits)
+}
+
+fn validate_latin1(data: &str) -> Result<(), BarcodeDataError> {
+    if data.is_empty() {
+        return Err(BarcodeDataError::Empty);
+    }
+    match data
+        .chars()
+        .enumerate()
+        .find(|(_, character)| *character as u32 > 0xFF)
+    {
+        Some((position, character)) => Err(BarcodeDataError::InvalidCharacter {
+            position,
+            character,
+        }),
+        None => Ok(()),
+    }
+}
+
+fn legacy_data_matrix_character(format_id: u8, character: char) -> bool {
+    match format_id {
+        1 => character.is_ascii_digit() || character == ' ',
+        2 => character.is_ascii_uppercase() || character == ' ',
+        3 => {
+            character.is_ascii_uppercase()
+                || character.is_ascii_digit()
+                || " .,-/".contains(character)
+        }
+        4 => character.is_ascii_uppercase() || character.is_ascii_digit() || character == ' ',
+        5 => character.is_ascii(),
+        _ => character as u32 <= 0xFF,
+    }
+}
+
+pub fn data_matrix_codewords(data: &str) -> usize {
+    let characters: Vec<char> = data.chars().collect();
+    let mut codewords = 0;
+    let mut index = 0;
+    while index < characters.len() {
+        let character = characters[index];
+        if character.is_ascii_digit()
+            && characters
+                .get(index + 1)
+                .is_some_and(|next| next.is_ascii_digit())
+        {
+            index += 2;
+        } else {
+            index += 1;
+        }
+        codewords += if character as u32 > 0x7F { 2 } else { 1 };
+    }
+    codewords
+}
+
+pub fn data_matrix_size(
+    data: &str,
+    columns: Option<u32>,
+    rows: Option<u32>,
+) -> Result<(u32, u32), BarcodeDataError> {
+    validate_latin1(data)?;
+    let codewords = data_matrix_codewords(data);
+    if let Some(columns) = columns {
+        let rows = rows.unwrap_or(columns);
+        if let Some((_, capacity)) = DATA_MATRIX_SIZES
+            .iter()
+            .find(|(size, _)| *size == columns && *size == rows)
+            && codewords > *capacity
+        {
+            return Err(BarcodeDataError::Unencodable(format!(
+                "Data needs {} codewords but a {}x{} DataMatrix holds {}",
+                codewords, columns, rows, capacity
+            )));
+        }
+        return Ok((columns, rows));
+    }
+    DATA_MATRIX_SIZES
+        .iter()
+        .find(|(_, capacity)| codewords <= *capacity)
+        .map(|(size, _)| (*size, *size))
+        .ok_or_else(|| {
+            BarcodeDataError::Unencodable(format!(
+                "Data needs {} codewords but DataMatrix holds at most {}",
+                codewords,
+                DATA_MATRIX_SIZES[DATA_MATRIX_SIZES.len() - 1].1
+            ))
+        })
+}
+
+pub fn validate_data_matrix(
+    data: &str,
+    quality: u32,
+    format_id: u8,
+    columns: Option<u32>,
+    rows: Option<u32>,
+) -> Result<(), BarcodeDataError> {
+    if quality == 200 {
+        return data_matrix_size(data, columns, rows).map(drop);
+    }
+    if data.is_empty() {
+        return Err(BarcodeDataError::Empty);
+    }
+    if let Some((position, character)) = data
+        .chars()
+        .enumerate()
+        .find(|(_, character)| !legacy_data_matrix_character(format_id, *character))
+    {
+        return Err(BarcodeDataError::InvalidCharacter {
+            position,
+            character,
+        });
+    }
+    let limit = DATA_MATRIX_LEGACY_LIMITS[format_id.min(6) as usize];
+    let length = data.chars().count();
+    if length > limit {
+        return Err(BarcodeDataError::Unencodable(format!(
+            "ECC {} DataMatrix with format {} holds at most {} characters, got {}",
+            quality, format_id, limit, length
+        )));
+    }
+    Ok(())
+}
+
+pub fn pdf417_codewords(data: &str, security_level: u8) -> usize {
+    let length = data.chars().count();
+    let data_codewords = if data.chars().all(|character| character.is_ascii_digit()) {
+        (length * 10).div_ceil(29) + 1
+    } else if data
+        .chars()
+        .all(|character| matches!(character, ' '..='~' | '\t' | '\r' | '\n'))
+    {
+        data.chars()
+            .map(|character| {
+                if character.is_ascii_alphanumeric() || character == ' ' {
+                    1
+                } else {
+                    2
+                }
+            })
+            .sum::<usize>()
+            .div_ceil(2)
+    } else {
+        length.div_ceil(6) * 5 + 1
+    };
+    1 + data_codewords + (2 << security_level.min(8))
+}
+
+pub fn pdf417_size(
+    data: &str,
+    security_level: u8,
+    columns: Option<u32>,
+    rows: Option<u32>,
+) -> Result<(u32, u32), BarcodeDataError> {
+    validate_latin1(data)?;
+    let codewords = pdf417_codewords(data, security_level);
+    if codewords > PDF417_MAX_CODEWORDS {
+        return Err(BarcodeDataError::Unencodable(format!(
+            "Data needs {} codewords at security level {} but PDF417 holds at most {}",
+            codewords, security_level, PDF417_MAX_CODEWORDS
+        )));
+    }
+    let total = codewords as u32;
+    let columns = columns.unwrap_or_else(|| {
+        let columns = ((total as f64 / 3.0).sqrt().ceil() as u32).clamp(1, 30);
+        columns.max(total.div_ceil(90))
+    });
+    let rows = rows.unwrap_or_else(|| total.div_ceil(columns).clamp(3, 90));
+    if columns * rows < total {
+        return Err(BarcodeDataError::Unencodable(format!(
+            "Data needs {} codewords but a {}x{} PDF417 holds {}",
+            total,
+            columns,
+            rows,
+            columns * rows
+        )));
+    }
+    Ok((columns, rows))
+}
+
+pub fn validate_barcode_data(barcode: &ZplCommand, data: &str) -> Result<(), BarcodeDataError> {
+    match barcode {
+        ZplCommand::Code128Barcode {
+            check_digit, mode, ..
+        } => encode_code128(data, *mode, *check_digit)
+            .map(drop)
+            .map_err(BarcodeDataError::Unencodable),
+        ZplCommand::Code39Barcode { .. } => validate_code39(data),
+        ZplCommand::Ean13Barcode { .. } => validate_ean13(data).map(drop),
+        ZplCommand::UpcABarcode { .. } => validate_upc_a(data).map(drop),
+        ZplCommand::Ean8Barcode { .. } => validate_ean8(data).map(drop),
+        ZplCommand::Interleaved2of5Barcode { check_digit, .. } => {
+            validate_interleaved_2of5(data, *check_digit).map(drop)
+        }
+        ZplCommand::QrCode { .. } => {
+            let field = data
+                .parse::<QrFieldData>()
+                .map_err(BarcodeDataError::Unencodable)?;
+            qr_code_symbol(&field)
+                .map(drop)
+                .map_err(BarcodeDataError::Unencodable)
+        }
+        ZplCommand::DataMatrix {
+            quality,
+            format_id,
+            columns,
+            rows,
+            ..
+        } => validate_data_matrix(data, *quality, *format_id, *columns, *rows),
+        ZplCommand::Pdf417 {
+            security_level,
+            columns,
+            rows,
+            ..
+        } => pdf417_size(data, *security_level, *columns, *rows).map(drop),
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_label_barcodes(commands: &[ZplCommand]) -> Vec<(usize, BarcodeDataError)> {
+    let mut errors = Vec::new();
+    let mut barcode = None;
+    for (index, command) in commands.iter().enumerate() {
+        match command {
+            ZplCommand::FieldData { data } => {
+                if let Some(barcode) = barcode
+                    && let Err(error) = validate_barcode_data(barcode, data)
+                {
+                    errors.push((index, error));
+                }
+            }
+            ZplCommand::FieldSeparator | ZplCommand::StartFormat | ZplCommand::EndFormat => {
+                barcode = None;
+            }
+            command if command.is_barcode() => barcode = Some(command),
+            _ => {}
+        }
+    }
+    errors
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Code128Set {
+    A,
+    B,
+    C,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Code128Input {
+    Char(u8),
+    Function(u8),
+    Shift,
+    Switch(Code128Set),
+    Start(Code128Set),
+}
+
+pub fn mod10_check_digit(digits: &str) -> Option<u8> {
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(index, byte)| {
+            let value = (byte - b'0') as u32;
+            if index % 2 == 0 { value * 3 } else { value }
+        })
+        .sum();
+    Some(((10 - sum % 10) % 10) as u8)
+}
+
+fn parse_code128_data(data: &str) -> Vec<Code128Input> {
+    let bytes: Vec<u8> = data.bytes().collect();
+    let mut inputs = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] == b'>' && index + 1 < bytes.len() {
+            let input = match bytes[index + 1] {
+                b'<' => Some(Code128Input::Char(b'>')),
+                b'0' => Some(Code128Input::Char(b'^')),
+                b'=' => Some(Code128Input::Char(b'~')),
+                b'1' => Some(Code128Input::Char(127)),
+                b'2' => Some(Code128Input::Function(96)),
+                b'3' => Some(Code128Input::Function(97)),
+                b'4' => Some(Code128Input::Shift),
+                b'5' => Some(Code128Input::Switch(Code128Set::C)),
+                b'6' => Some(Code128Input::Switch(Code128Set::B)),
+                b'7' => Some(Code128Input::Switch(Code128Set::A)),
+                b'8' => Some(Code128Input::Function(CODE128_FNC1)),
+                b'9' => Some(Code128Input::Start(Code128Set::A)),
+                b':' => Some(Code128Input::Start(Code128Set::B)),
+                b';' => Some(Code128Input::Start(Code128Set::C)),
+                _ => None,
+            };
+            if let Some(input) = input {
+                inputs.push(input);
+                index += 2;
+                continue;
+            }
+        }
+        inputs.push(Code128Input::Char(bytes[index]));
+        index += 1;
+    }
+
+    inputs
+}
+
+fn set_value(set: Code128Set, character: u8) -> Option<u8> {
+    match set {
+        Code128Set::A if character < 32 => Some(character + 64),
+        Code128Set::A if character < 96 => Some(character - 32),
+        Code128Set::B if (32..128).contains(&character) => Some(character - 32),
+        _ => None,
+    }
+}
+
+fn switch_value(set: Code128Set) -> u8 {
+    match set {
+        Code128Set::A => CODE128_CODE_A,
+        Code128Set::B => CODE128_CODE_B,
+        Code128Set::C => CODE128_CODE_C,
+    }
+}
+
+fn start_value(set: Code128Set) -> u8 {
+    match set {
+        Code128Set::A => CODE128_START_A,
+        Code128Set::B => CODE128_START_B,
+        Code128Set::C => CODE128_START_C,
+    }
+}
+
+fn fnc4_value(set: Code128Set) -> Option<u8> {
+    match set {
+        Code128Set::A => Some(CODE128_CODE_A),
+        Code128Set::B => Some(CODE128_CODE_B),
+        Code128Set::C => None,
+    }
+}
+
+fn digit_run(inputs: &[Code128Input], start: usize) -> usize {
+    inputs[start..]
+        .iter()
+        .take_while(|input| matches!(input, Code128Input::Char(byte) if byte.is_ascii_digit()))
+        .count()
+}
+
+fn preferred_text_set(inputs: &[Code128Input], start: usize) -> Code128Set {
+    for input in &inputs[start..] {
+        if let Code128Input::Char(character) = input {
+            let character = character & 0x7F;
+            if character < 32 {
+                return Code128Set::A;
+            }
+            if character >= 96 {
+                return Code128Set::B;
+            }
+        }
+    }
+    Code128Set::B
+}
+
+fn encode_character(codewords: &mut Vec<u8>, set: Code128Set, character: u8) -> Result<(), String> {
+    let mut character = character;
+    if character >= 128 {
+        codewords.push(fnc4_value(set).ok_or("Extended ASCII requires subset A or B")?);
+        character -= 128;
+    }
+    let value = set_value(set, character)
+        .ok_or_else(|| format!("Character 0x{:02X} is not in subset {:?}", character, set))?;
+    codewords.push(value);
+    Ok(())
+}
+
+fn encode_automatic(inputs: &[Code128Input]) -> Result<Vec<u8>, String> {
+    let leading_digits = digit_run(inputs, 0);
+    let starts_with_fnc1 = matches!(inputs.first(), Some(Code128Input::Function(CODE128_FNC1)));
+    let mut set = if leading_digits >= 4
+        || (leading_digits == inputs.len()
+            && leading_digits >= 2
+            && leading_digits.is_multiple_of(2))
+        || (starts_with_fnc1 && digit_run(inputs, 1) >= 2)
+    {
+        Code128Set::C
+    } else {
+        preferred_text_set(inputs, 0)
+    };
+    let mut codewords = vec![start_value(set)];
+    let mut index = 0;
+
+    while index < inputs.len() {
+        match inputs[index] {
+            Code128Input::Function(value) => {
+                codewords.push(value);
+                index += 1;
+            }
+            Code128Input::Char(character) => {
+                let run = digit_run(inputs, index);
+                if set == Code128Set::C {
+                    if run >= 2 {
+                        codewords.push((character - b'0') * 10 + (inputs_digit(inputs, index + 1)));
+                        index += 2;
+                        continue;
+                    }
+                    set = preferred_text_set(inputs, index);
+                    codewords.push(switch_value(set));
+                    continue;
+                }
+
+                let remaining = inputs.len() - index;
+                if run >= 6 || (run >= 4 && run == remaining) {
+                    if run % 2 == 1 {
+                        encode_character(&mut codewords, set, character)?;
+                        index += 1;
+                    }
+                    set = Code128Set::C;
+                    codewords.push(CODE128_CODE_C);
+                    continue;
+                }
+
+                if set_value(set, character & 0x7F).is_some() {
+                    encode_character(&mut codewords, set, character)?;
+                    index += 1;
+                    continue;
+                }
+
+                let other = if set == Code128Set::A {
+                    Code128Set::B
+                } else {
+                    Code128Set::A
+                };
+                let next_fits_current = match inputs.get(index + 1) {
+                    Some(Code128Input::Char(next)) => set_value(set, next & 0x7F).is_some(),
+                    _ => false,
+                };
+                if next_fits_current {
+                    codewords.push(CODE128_SHIFT);
+                    encode_character(&mut codewords, other, character)?;
+                } else {
+                    set = other;
+                    codewords.push(switch_value(set));
+                    encode_character(&mut codewords, set, character)?;
+                }
+                index += 1;
+            }
+            _ => index += 1,
+        }
+    }
+
+    Ok(codewords)
+}
+
+fn inputs_digit(inputs: &[Code128Input], index: usize) -> u8 {
+    match inputs.get(index) {
+        Some(Code128Input::Char(byte)) => byte - b'0',
+        _ => 0,
+    }
+}
+
+fn encode_manual(inputs: &[Code128Input]) -> Result<Vec<u8>, String> {
+    let (mut set, skip) = match inputs.first() {
+        Some(Code128Input::Start(set)) => (*set, 1),
+        _ => (Code128Set::B, 0),
+    };
+    let mut codewords = vec![start_value(set)];
+    let mut index = skip;
+    let mut shifted = false;
+
+    while index < inputs.len() {
+        let active_set = if shifted {
+            match set {
+                Code128Set::A => Code128Set::B,
+                Code128Set::B => Code128Set::A,
+                Code128Set::C => Code128Set::C,
+            }
+        } else {
+            set
+        };
+        shifted = false;
+
+        match inputs[index] {
+            Code128Input::Char(character) if active_set == Code128Set::C => {
+                if digit_run(inputs, index) < 2 {
+                    return Err(format!(
+                        "Subset C requires digit pairs, found '{}'",
+                        character as char
+                    ));
+                }
+                codewords.push((character - b'0') * 10 + inputs_digit(inputs, index + 1));
+                index += 2;
+                continue;
+            }
+            Code128Input::Char(character) => {
+                encode_character(&mut codewords, active_set, character)?;
+            }
+            Code128Input::Function(value) => codewords.push(value),
+            Code128Input::Shift => {
+                if set == Code128Set::C {
+                    return Err("Shift is not available in subset C".to_string());
+                }
+                codewords.push(CODE128_SHIFT);
+                shifted = true;
+            }
+            Code128Input::Switch(target) => {
+                if set == Code128Set::C && target == Code128Set::C {
+                    return Err("Already in subset C".to_string());
+                }
+                codewords.push(switch_value(target));
+                set = target;
+            }
+            Code128Input::Start(_) => {
+                return Err("Start codes are only valid at the beginning of the data".to_string());
+            }
+        }
+        index += 1;
+    }
+
+    Ok(codewords)
+}
+
+fn ucc_case_digits(data: &str) -> Result<String, String> {
+    let digits: String = data.chars().filter(|character| *character != ' ').collect();
+    if !digits.chars().all(|character| character.is_ascii_digit()) {
+        return Err("UCC case mode requires numeric data".to_string());
+    }
+    let mut digits: String = digits.chars().take(19).collect();
+    while digits.len() < 19 {
+        digits.push('0');
+    }
+    let check = mod10_check_digit(&digits).unwrap_or(0);
+    digits.push((b'0' + check) as char);
+    Ok(digits)
+}
+
+pub fn code128_interpretation(data: &str, mode: Code128Mode, check_digit: bool) -> String {
+    if mode == Code128Mode::UccCase {
+        return ucc_case_digits(data).unwrap_or_default();
+    }
+
+    let mut text: String = parse_code128_data(data)
+        .iter()
+        .filter_map(|input| match input {
+            Code128Input::Char(character) if *character >= 32 && *character != 127 => {
+                Some(*character as char)
+            }
+            _ => None,
+        })
+        .collect();
+
+    if check_digit && let Some(check) = mod10_check_digit(&text) {
+        text.push((b'0' + check) as char);
+    }
+    text
+}
+
+pub fn encode_code128(data: &str, mode: Code128Mode, check_digit: bool) -> Result<Vec<u8>, String> {
+    let mut codewords = match mode {
+        Code128Mode::NoSelection => {
+            let mut inputs = parse_code128_data(data);
+            if check_digit {
+                append_check_digit(&mut inputs, data)?;
+            }
+            encode_manual(&inputs)?
+        }
+        Code128Mode::Automatic => {
+            let mut inputs = automatic_inputs(parse_code128_data(data));
+            if check_digit {
+                append_check_digit(&mut inputs, data)?;
+            }
+            encode_automatic(&inputs)?
+        }
+        Code128Mode::UccEan => {
+            let mut inputs = vec![Code128Input::Function(CODE128_FNC1)];
+            inputs.extend(
+                automatic_inputs(parse_code128_data(data))
+                    .into_iter()
+                    .filter(|input| {
+                        !matches!(input, Code128Input::Char(b'(') | Code128Input::Char(b')'))
+                    }),
+            );
+            encode_automatic(&inputs)?
+        }
+        Code128Mode::UccCase => {
+            let mut inputs = vec![Code128Input::Function(CODE128_FNC1)];
+            inputs.extend(ucc_case_digits(data)?.bytes().map(Code128Input::Char));
+            encode_automatic(&inputs)?
+        }
+    };
+
+    if codewords.len() < 2 {
+        return Err("Code 128 data is empty".to_string());
+    }
+
+    let checksum = codewords
+        .iter()
+        .enumerate()
+        .map(|(position, value)| position.max(1) as u32 * *value as u32)
+        .sum::<u32>()
+        % 103;
+    codewords.push(checksum as u8);
+    codewords.push(CODE128_STOP);
+
+    Ok(codewords)
+}
+
+fn automatic_inputs(inputs: Vec<Code128Input>) -> Vec<Code128Input> {
+    inputs
+        .into_iter()
+        .filter(|input| {
+            !matches!(
+                input,
+                Code128Input::Switch(_) | Code128Input::Start(_) | Code128Input::Shift
+            )
+        })
+        .collect()
+}
+
+fn append_check_digit(inputs: &mut Vec<Code128Input>, data: &str) -> Result<(), String> {
+    let digits = code128_interpretation(data, Code128Mode::NoSelection, false);
+    let check = mod10_check_digit(&digits).ok_or("Check digit requires numeric data")?;
+    inputs.push(Code128Input::Char(b'0' + check));
+    Ok(())
+}
+
+pub fn code128_symbol(
+    data: &str,
+    mode: Code128Mode,
+    check_digit: bool,
+    module_width: u32,
+) -> Result<LinearSymbol, String> {
+    let codewords = encode_code128(data, mode, check_digit)?;
+    let module_width = module_width.max(1);
+    let elements = codewords
+        .iter()
+        .flat_map(|codeword| CODE128_PATTERNS[*codeword as usize].bytes())
+        .map(|width| (width - b'0') as u32 * module_width)
+        .collect();
+
+    Ok(LinearSymbol {
+        elements,
+        text: code128_interpretation(data, mode, check_digit),
+    })
+}
+
+pub fn qr_code_symbol(field: &QrFieldData) -> Result<MatrixSymbol, String> {
+    let ec_level = match field.error_correction {
+        QrErrorCorrection::UltraHigh => EcLevel::H,
+        QrErrorCorrection::High => EcLevel::Q,
+        QrErrorCorrection::Standard => EcLevel::M,
+        QrErrorCorrection::HighDensity => EcLevel::L,
+    };
+    let data = field.data.as_bytes();
+
+    let code = match field.input_mode {
+        QrInputMode::Automatic => QrCode::with_error_correction_level(data, ec_level),
+        mode => {
+            let bits = (1..=40)
+                .find_map(|version| {
+                    let mut bits = Bits::new(Version::Normal(version));
+                    let pushed = match mode {
+                        QrInputMode::Numeric => bits.push_numeric_data(data),
+                        QrInputMode::Alphanumeric => bits.push_alphanumeric_data(data),
+                        _ => bits.push_byte_data(data),
+                    };
+                    pushed.and_then(|_| bits.push_terminator(ec_level)).ok()?;
+                    Some(bits)
+                })
+                .ok_or("QR data does not fit in any symbol version")?;
+            QrCode::with_bits(bits, ec_level)
+        }
+    }
+    .map_err(|error| format!("QR encoding failed: {}", error))?;
+
+    let size = code.width() as u32;
+    Ok(MatrixSymbol {
+        columns: size,
+        rows: size,
+        modules: code
+            .to_colors()
+            .into_iter()
+            .map(|color| color == Color::Dark)
+            .collect(),
+    })
+}
+
+fn wide_width(module_width: u32, ratio: f32) -> u32 {
+    (module_width as f32 * ratio.clamp(2.0, 3.0)).round() as u32
+}
+
+fn wide_narrow_elements(pattern: &str, narrow: u32, wide: u32) -> impl Iterator<Item = u32> + '_ {
+    pattern
+        .chars()
+        .map(move |element| if element == 'w' { wide } else { narrow })
+}
+
+pub fn code39_symbol(
+    data: &str,
+    check_digit: bool,
+    module_width: u32,
+    ratio: f32,
+) -> Result<LinearSymbol, BarcodeDataError> {
+    validate_code39(data)?;
+    let narrow = module_width.max(1);
+    let wide = wide_width(narrow, ratio);
+
+    let mut indices = vec![CODE39_START_STOP];
+    indices.extend(
+        data.chars()
+            .filter_map(|character| CODE39_CHARSET.find(character)),
+    );
+    if check_digit && let Some(check) = mod43_check_digit(data) {
+        indices.extend(CODE39_CHARSET.find(check));
+    }
+    indices.push(CODE39_START_STOP);
+
+    let mut elements = Vec::new();
+    for (position, index) in indices.iter().enumerate() {
+        if position > 0 {
+            elements.push(narrow);
+        }
+        elements.extend(wide_narrow_elements(CODE39_PATTERNS[*index], narrow, wide));
+    }
+
+    Ok(LinearSymbol {
+        elements,
+        text: format!("*{}*", data),
+    })
+}
+
+pub fn interleaved_2of5_symbol(
+    data: &str,
+    check_digit: bool,
+    module_width: u32,
+    ratio: f32,
+) -> Result<LinearSymbol, BarcodeDataError> {
+    let digits = validate_interleaved_2of5(data, check_digit)?;
+    let narrow = module_width.max(1);
+    let wide = wide_width(narrow, ratio);
+
+    let mut elements = vec![narrow; 4];
+    let digit_values: Vec<usize> = digits
+        .bytes()
+        .map(|digit| (digit - b'0') as usize)
+        .collect();
+    for pair in digit_values.chunks(2) {
+        let bars = INTERLEAVED_2OF5_PATTERNS[pair[0]].chars();
+        let spaces = INTERLEAVED_2OF5_PATTERNS[pair[1]].chars();
+        for (bar, space) in bars.zip(spaces) {
+            elements.push(if bar == 'w' { wide } else { narrow });
+            elements.push(if space == 'w' { wide } else { narrow });
+        }
+    }
+    elements.extend([wide, narrow, narrow]);
+
+    Ok(LinearSymbol {
+        elements,
+        text: digits,
+    })
+}
+
+fn ean_digit_modules(digit: u8, set: char) -> String {
+    let left = EAN_L_PATTERNS[(digit - b'0') as usize];
+    let right: String = left
+        .chars()
+        .map(|module| if module == '1' { '0' } else { '1' })
+        .collect();
+    match set {
+        'L' => left.to_string(),
+        'G' => right.chars().rev().collect(),
+        _ => right,
+    }
+}
+
+fn ean13_modules(digits: &str) -> String {
+    let digits = digits.as_bytes();
+    let parity = EAN13_PARITY[(digits[0] - b'0') as usize];
+    let mut modules = String::from("101");
+    for (digit, set) in digits[1..7].iter().zip(parity.chars()) {
+        modules.push_str(&ean_digit_modules(*digit, set));
+    }
+    modules.push_str("01010");
+    for digit in &digits[7..13] {
+        modules.push_str(&ean_digit_modules(*digit, 'R'));
+    }
+    modules.push_str("101");
+    modules
+}
+
+fn modules_to_elements(modules: &str, module_width: u32) -> Vec<u32> {
+    let mut elements: Vec<u32> = Vec::new();
+    let mut previous = None;
+    for module in modules.chars() {
+        if previous == Some(module) {
+            if let Some(last) = elements.last_mut() {
+                *last += module_width;
+            }
+        } else {
+            elements.push(module_width);
+        }
+        previous = Some(module);
+    }
+    elements
+}
+
+pub fn ean13_symbol(data: &str, module_width: u32) -> Result<LinearSymbol, BarcodeDataError> {
+    let digits = validate_ean13(data)?;
+    Ok(LinearSymbol {
+        elements: modules_to_elements(&ean13_modules(&digits), module_width.max(1)),
+        text: digits,
+    })
+}
+
+pub fn upc_a_symbol(
+    data: &str,
+    print_check_digit: bool,
+    module_width: u32,
+) -> Result<LinearSymbol, BarcodeDataError> {
+    let digits = validate_upc_a(data)?;
+    let modules = ean13_modules(&format!("0{}", digits));
+    let text = if print_check_digit {
+        digits
+    } else {
+        digits[..11].to_string()
+    };
+    Ok(LinearSymbol {
+        elements: modules_to_elements(&modules, module_width.max(1)),
+        text,
+    })
+}
+
+pub fn ean8_symbol(data: &str, module_width: u32) -> Result<LinearSymbol, BarcodeDataError> {
+    let digits = validate_ean8(data)?;
+    let mut modules = String::from("101");
+    for digit in &digits.as_bytes()[..4] {
+        modules.push_str(&ean_digit_modules(*digit, 'L'));
+    }
+    modules.push_str("01010");
+    for digit in &digits.as_bytes()[4..] {
+        modules.push_str(&ean_digit_modules(*digit, 'R'));
+    }
+    modules.push_str("101");
+    Ok(LinearSymbol {
+        elements: modules_to_elements(&modules, module_width.max(1)),
+        text: digits,
+    })
+}
+
+pub fn code128_invocation_data(data: &str) -> Result<String, String> {
+    let codewords = encode_code128(data, Code128Mode::Automatic, false)?;
+    let mut set = Code128Set::B;
+    let mut shifted = false;
+    let mut output = String::new();
+
+    for codeword in &codewords[..codewords.len() - 2] {
+        let current = match (shifted, set) {
+            (true, Code128Set::A) => Code128Set::B,
+            (true, Code128Set::B) => Code128Set::A,
+            _ => set,
+        };
+        shifted = false;
+
+        match (*codeword, current) {
+            (CODE128_START_A, _) => {
+                output.push_str(">9");
+                set = Code128Set::A;
+            }
+            (CODE128_START_B, _) => {
+                output.push_str(">:");
+                set = Code128Set::B;
+            }
+            (CODE128_START_C, _) => {
+                output.push_str(">;");
+                set = Code128Set::C;
+            }
+            (CODE128_FNC1, _) => output.push_str(">8"),
+            (value, Code128Set::C) if value < 100 => output.push_str(&format!("{:02}", value)),
+            (CODE128_CODE_C, _) => {
+                output.push_str(">5");
+                set = Code128Set::C;
+            }
+            (CODE128_CODE_B, Code128Set::A | Code128Set::C) => {
+                output.push_str(">6");
+                set = Code128Set::B;
+            }
+            (CODE128_CODE_A, Code128Set::B | Code128Set::C) => {
+                output.push_str(">7");
+                set = Code128Set::A;
+            }
+            (CODE128_SHIFT, _) => {
+                output.push_str(">4");
+                shifted = true;
+            }
+            (96, _) => output.push_str(">2"),
+            (97, _) => output.push_str(">3"),
+            (value, Code128Set::A) if value >= 64 => output.push((value - 64) as char),
+            (value, _) => match value + 32 {
+                b'>' => output.push_str("><"),
+                b'^' => output.push_str(">0"),
+                b'~' => output.push_str(">="),
+                127 => output.push_str(">1"),
+                character => output.push(character as char),
+            },
+        }
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn module_bits(symbol: &LinearSymbol) -> String {
+        symbol
+            .modules()
+            .iter()
+            .map(|dark| if *dark { '1' } else { '0' })
+            .collect()
+    }
+
+    #[test]
+    fn check_digits_are_appended() {
+        assert_eq!(validate_ean13("400638133393").unwrap(), "4006381333931");
+        assert_eq!(validate_upc_a("03600029145").unwrap(), "036000291452");
+        assert_eq!(validate_ean8("9638507").unwrap(), "96385074");
+        assert_eq!(mod43_check_digit("CODE39"), Some('W'));
+        assert!(validate_ean13("4006381333932").is_err());
+    }
+
+    #[test]
+    fn code128_packs_digit_pairs_in_code_c() {
+        let codewords = encode_code128("12345678", Code128Mode::Automatic, false).unwrap();
+        assert_eq!(&codewords[..5], &[CODE128_START_C, 12, 34, 56, 78]);
+    }
+
+    #[test]
+    fn ean13_leading_zero_uses_llllll_parity() {
+        let symbol = ean13_symbol("0123456789012", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10100110010010011011110101000110110001010111101010100010010010001110100111001011001101101100101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_one_uses_llglgg_parity() {
+        let symbol = ean13_symbol("1234567890128", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10100100110111101001110101100010000101001000101010100100011101001110010110011011011001001000101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_two_uses_llgglg_parity() {
+        let symbol = ean13_symbol("2345678901234", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101111010100011011100100001010111011000100101010111010011100101100110110110010000101011100101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_three_uses_llgggl_parity() {
+        let symbol = ean13_symbol("3456789012340", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101000110110001000010100100010001001000101101010111001011001101101100100001010111001110010101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_four_uses_lgllgg_parity() {
+        let symbol = ean13_symbol("4567890123456", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101100010000101011101101101110010111010011101010110011011011001000010101110010011101010000101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_five_uses_lggllg_parity() {
+        let symbol = ean13_symbol("5678901234562", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101011110010001000100100010110001101011001101010110110010000101011100100111010100001101100101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_six_uses_lgggll_parity() {
+        let symbol = ean13_symbol("6901234567892", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10100010110100111011001100110110111101010001101010100111010100001000100100100011101001101100101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_seven_uses_lglglg_parity() {
+        let symbol = ean13_symbol("7501031311309", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101100010100111001100101001110111101011001101010100001011001101100110100001011100101110100101"
+        );
+    }
+
+    #[test]
+    fn ean13_leading_eight_uses_lglggl_parity() {
+        let symbol = ean13_symbol("8711253001202", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10101110110110011001100100110110111001011110101010111001011100101100110110110011100101101100101"
+        );
+    }
+    #[test]
+    fn ean13_leading_nine_uses_lgglgl_parity() {
+        let symbol = ean13_symbol("9012345678906", 1).unwrap();
+        assert_eq!(
+            module_bits(&symbol),
+            "10100011010110011001101101111010011101011000101010101000010001001001000111010011100101010000101"
+        );
+    }
+    #[test]
+    fn data_matrix_picks_the_smallest_square_symbol() {
+        assert_eq!(data_matrix_codewords("123456"), 3);
+        assert_eq!(data_matrix_size("123456", None, None).unwrap(), (10, 10));
+        assert_eq!(data_matrix_size("ABCDEF", None, None).unwrap(), (14, 14));
+        assert!(data_matrix_size("ABCDEF", Some(10), Some(10)).is_err());
+        assert!(data_matrix_size(&"9".repeat(3118), None, None).is_err());
+        assert!(matches!(
+            data_matrix_size("Ω", None, None),
+            Err(BarcodeDataError::InvalidCharacter { .. })
+        ));
+    }
+
+    #[test]
+    fn legacy_data_matrix_checks_the_format_character_set() {
+        assert!(validate_data_matrix("12 34", 0, 1, None, None).is_ok());
+        assert!(matches!(
+            validate_data_matrix("12A", 0, 1, None, None),
+            Err(BarcodeDataError::InvalidCharacter { position: 2, .. })
+        ));
+        assert!(validate_data_matrix(&"1".repeat(597), 0, 1, None, None).is_err());
+    }
+
+    #[test]
+    fn pdf417_capacity_depends_on_security_level() {
+        assert_eq!(pdf417_codewords("1234567890", 0), 1 + 5 + 2);
+        assert!(pdf417_size(&"A".repeat(1700), 0, None, None).is_ok());
+        assert!(pdf417_size(&"A".repeat(1700), 8, None, None).is_err());
+        assert!(pdf417_size("HELLO WORLD", 2, Some(1), Some(3)).is_err());
+        let (columns, rows) = pdf417_size("HELLO WORLD", 2, None, None).unwrap();
+        assert!(columns * rows >= pdf417_codewords("HELLO WORLD", 2) as u32);
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+
+use crate::zpl::ZplCommand;
+
+const CLOCK_CODES: &str = "aAbBdHIjmMpSUWwyY";
+
+pub fn expand_clock_data(data: &str, indicators: &[char], time: NaiveDateTime) -> String {
+    let mut result = String::with_capacity(data.len());
+    let mut chars = data.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if indicators.contains(&character)
+            && let Some(&code) = chars.peek()
+            && CLOCK_CODES.contains(code)
+        {
+            chars.next();
+            result.push_str(&time.format(&format!("%{}", code)).to_string());
+        } else {
+            result.push(character);
+        }
+    }
+
+    result
+}
+
+pub fn set_time_command(time: NaiveDateTime) -> ZplCommand {
+    ZplCommand::SetTime {
+        month: time.month(),
+        day: time.day(),
+        year: time.year().clamp(1998, 2097) as u32,
+        hour: time.hour(),
+        minute: time.minute(),
+        second: time.second(),
+        twenty_four_hour: true,
+    }
+}
+
+pub fn local_time() -> NaiveDateTime {
+    Local::now().naive_local()
+}
@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use ab_glyph::FontRef;
+
+use crate::printer::{ZplPrinter, query_printer, send_to_printer};
+use crate::printer_status::PrinterInfo;
+use crate::zpl::{
+    FontDownload, FontName, FontOrientation, ZplCommand, commands_to_zpl, is_font_letter,
+    is_font_path,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrueTypeFont {
+    pub path: String,
+    pub method: FontDownload,
+    pub data: Vec<u8>,
+}
+
+impl TrueTypeFont {
+    pub fn from_bytes(
+        device: char,
+        name: &str,
+        method: FontDownload,
+        data: Vec<u8>,
+    ) -> Result<Self, String> {
+        let name = name.trim().to_uppercase();
+        if name.is_empty()
+            || name.len() > 16
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!("Invalid font name: {}", name));
+        }
+        let path = format!(
+            "{}:{}.{}",
+            device.to_ascii_uppercase(),
+            name,
+            method.extension()
+        );
+        if !is_font_path(&path) {
+            return Err(format!("Invalid font path: {}", path));
+        }
+        FontRef::try_from_slice(&data).map_err(|e| format!("Invalid TrueType font: {}", e))?;
+        Ok(Self { path, method, data })
+    }
+
+    pub fn open(
+        file: impl AsRef<Path>,
+        device: char,
+        name: &str,
+        method: FontDownload,
+    ) -> Result<Self, String> {
+        let data = std::fs::read(file.as_ref())
+            .map_err(|e| format!("Failed to read {}: {}", file.as_ref().display(), e))?;
+        Self::from_bytes(device, name, method, data)
+    }
+
+    pub fn download_command(&self) -> ZplCommand {
+        ZplCommand::DownloadFont {
+            path: self.path.clone(),
+            method: self.method,
+            data: self
+                .data
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect(),
+        }
+    }
+
+    pub fn download_zpl(&self) -> String {
+        commands_to_zpl(&[self.download_command()])
+    }
+
+    pub fn handle(&self) -> FontHandle {
+        let size = match self.method {
+            FontDownload::TrueType => self.data.len() as u64,
+            FontDownload::Unbounded => 0,
+        };
+        FontHandle {
+            path: self.path.clone(),
+            size,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FontHandle {
+    pub path: String,
+    pub size: u64,
+}
+
+impl FontHandle {
+    pub fn font_name(&self) -> FontName {
+        FontName::Device(self.path.clone())
+    }
+
+    pub fn font(&self, orientation: FontOrientation, height: u32, width: u32) -> ZplCommand {
+        ZplCommand::Font {
+            font: self.font_name(),
+            orientation,
+            height,
+            width,
+        }
+    }
+
+    pub fn alias(&self, letter: char) -> Result<ZplCommand, String> {
+        if !is_font_letter(letter) {
+            return Err(format!("Invalid font alias: {}", letter));
+        }
+        Ok(ZplCommand::FontAlias {
+            alias: letter.to_ascii_uppercase(),
+            path: self.path.clone(),
+        })
+    }
+
+    pub fn device(&self) -> char {
+        self.path.chars().next().unwrap_or('R')
+    }
+}
+
+pub fn font_directory_query(device: char) -> String {
+    format!("^XA^HW{}:*.*^XZ", device.to_ascii_uppercase())
+}
+
+pub fn font_present(printer: &ZplPrinter, handle: &FontHandle) -> Result<bool, String> {
+    let response = query_printer(printer, &font_directory_query(handle.device()))?;
+    Ok(is_listed(&response, handle))
+}
+
+fn is_listed(response: &str, handle: &FontHandle) -> bool {
+    PrinterInfo::parse_directory_listing(response)
+        .iter()
+        .any(|file| {
+            file.path.eq_ignore_ascii_case(&handle.path)
+                && (handle.size == 0 || file.size == handle.size)
+        })
+}
+
+pub fn download_font(printer: &ZplPrinter, font: &TrueTypeFont) -> Result<FontHandle, String> {
+    send_to_printer(printer, &font.download_zpl())?;
+    Ok(font.handle())
+}
+
+pub fn ensure_font(printer: &ZplPrinter, font: &TrueTypeFont) -> Result<FontHandle, String> {
+    let handle = font.handle();
+    if font_present(printer, &handle)? {
+        Ok(handle)
+    } else {
+        download_font(printer, font)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn font(path: &str, method: FontDownload) -> TrueTypeFont {
+        TrueTypeFont {
+            path: path.to_string(),
+            method,
+            data: vec![0; 1200],
+        }
+    }
+
+    #[test]
+    fn truetype_fonts_match_on_size() {
+        let handle = font("E:BRAND.TTF", FontDownload::TrueType).handle();
+        assert!(is_listed("* E:BRAND.TTF 1200\r\n", &handle));
+        assert!(!is_listed("* E:BRAND.TTF 900\r\n", &handle));
+    }
+
+    #[test]
+    fn unbounded_fonts_match_on_path() {
+        let handle = font("E:BRAND.FNT", FontDownload::Unbounded).handle();
+        assert!(is_listed("* E:BRAND.FNT 48512\r\n", &handle));
+        assert!(!is_listed("* E:OTHER.FNT 1200\r\n", &handle));
+    }
+}
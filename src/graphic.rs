@@ -0,0 +1,663 @@
+use std::io::{Read, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use image::{DynamicImage, GenericImageView, GrayImage, Luma};
+use serde::{Deserialize, Serialize};
+
+use crate::zpl::{GraphicEncoding, ZplCommand};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DitherMode {
+    #[default]
+    Threshold,
+    FloydSteinberg,
+    Atkinson,
+    Bayer4x4,
+    Bayer8x8,
+}
+
+impl DitherMode {
+    pub fn all() -> [DitherMode; 5] {
+        [
+            DitherMode::Threshold,
+            DitherMode::FloydSteinberg,
+            DitherMode::Atkinson,
+            DitherMode::Bayer4x4,
+            DitherMode::Bayer8x8,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DitherMode::Threshold => "Threshold",
+            DitherMode::FloydSteinberg => "Floyd-Steinberg",
+            DitherMode::Atkinson => "Atkinson",
+            DitherMode::Bayer4x4 => "Bayer 4x4",
+            DitherMode::Bayer8x8 => "Bayer 8x8",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImageConversion {
+    pub mode: DitherMode,
+    pub threshold: u8,
+    pub invert: bool,
+    pub gamma: f32,
+    pub alpha_as_white: bool,
+}
+
+impl Default for ImageConversion {
+    fn default() -> Self {
+        Self {
+            mode: DitherMode::Threshold,
+            threshold: 128,
+            invert: false,
+            gamma: 1.0,
+            alpha_as_white: false,
+        }
+    }
+}
+
+pub fn image_to_zpl_hex_with(image: &DynamicImage, conversion: &ImageConversion) -> String {
+    let width = image.width() as usize;
+    let height = image.height() as usize;
+    let bytes_per_row = width.div_ceil(8);
+
+    let mut luminance: Vec<f32> = image
+        .pixels()
+        .map(|(_, _, pixel)| {
+            let [r, g, b, a] = pixel.0.map(|channel| channel as u32);
+            let mut value = ((r * 299 + g * 587 + b * 114) / 1000) as f32;
+            if conversion.alpha_as_white {
+                let alpha = a as f32 / 255.0;
+                value = value * alpha + 255.0 * (1.0 - alpha);
+            }
+            if conversion.gamma > 0.0 && conversion.gamma != 1.0 {
+                value = 255.0 * (value / 255.0).powf(conversion.gamma);
+            }
+            if conversion.invert {
+                value = 255.0 - value;
+            }
+            value
+        })
+        .collect();
+
+    let black = dither(&mut luminance, width, height, conversion);
+
+    let mut hex = String::with_capacity(bytes_per_row * height * 2);
+    for row in black.chunks(width.max(1)).take(height) {
+        let mut row_bytes = vec![0u8; bytes_per_row];
+        for (x, _) in row.iter().enumerate().filter(|(_, is_black)| **is_black) {
+            row_bytes[x / 8] |= 0x80 >> (x % 8);
+        }
+        hex.push_str(&bytes_to_hex(&row_bytes));
+    }
+    hex
+}
+
+fn dither(
+    luminance: &mut [f32],
+    width: usize,
+    height: usize,
+    conversion: &ImageConversion,
+) -> Vec<bool> {
+    let threshold = conversion.threshold as f32;
+    let mut black = vec![false; luminance.len()];
+
+    match conversion.mode {
+        DitherMode::Threshold => {
+            for (pixel, value) in black.iter_mut().zip(luminance.iter()) {
+                *pixel = *value < threshold;
+            }
+        }
+        DitherMode::Bayer4x4 | DitherMode::Bayer8x8 => {
+            let size = if conversion.mode == DitherMode::Bayer4x4 {
+                4
+            } else {
+                8
+            };
+            let matrix = bayer_matrix(size);
+            let levels = (size * size) as f32;
+            for y in 0..height {
+                for x in 0..width {
+                    let offset = (matrix[(y % size) * size + x % size] as f32 + 0.5) / levels - 0.5;
+                    black[y * width + x] = luminance[y * width + x] + offset * 255.0 < threshold;
+                }
+            }
+        }
+        DitherMode::FloydSteinberg | DitherMode::Atkinson => {
+            let spread: &[(isize, usize, f32)] = if conversion.mode == DitherMode::FloydSteinberg {
+                &[
+                    (1, 0, 7.0 / 16.0),
+                    (-1, 1, 3.0 / 16.0),
+                    (0, 1, 5.0 / 16.0),
+                    (1, 1, 1.0 / 16.0),
+                ]
+            } else {
+                &[
+                    (1, 0, 1.0 / 8.0),
+                    (2, 0, 1.0 / 8.0),
+                    (-1, 1, 1.0 / 8.0),
+                    (0, 1, 1.0 / 8.0),
+                    (1, 1, 1.0 / 8.0),
+                    (0, 2, 1.0 / 8.0),
+                ]
+            };
+            for y in 0..height {
+                for x in 0..width {
+                    let index = y * width + x;
+                    let value = luminance[index];
+                    let is_black = value < threshold;
+                    black[index] = is_black;
+                    let error = value - if is_black { 0.0 } else { 255.0 };
+                    for &(dx, dy, weight) in spread {
+                        let target_x = x as isize + dx;
+                        let target_y = y + dy;
+                        if target_x >= 0 && (target_x as usize) < width && target_y < height {
+                            luminance[target_y * width + target_x as usize] += error * weight;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    black
+}
+
+fn bayer_matrix(size: usize) -> Vec<u32> {
+    let mut matrix = vec![0u32];
+    let mut current = 1;
+    while current < size {
+        let next = current * 2;
+        let mut expanded = vec![0u32; next * next];
+        for y in 0..current {
+            for x in 0..current {
+                let value = matrix[y * current + x] * 4;
+                expanded[y * next + x] = value;
+                expanded[y * next + x + current] = value + 2;
+                expanded[(y + current) * next + x] = value + 3;
+                expanded[(y + current) * next + x + current] = value + 1;
+            }
+        }
+        matrix = expanded;
+        current = next;
+    }
+    matrix
+}
+
+pub fn encode_graphic_data(hex: &str, bytes_per_row: u32, encoding: GraphicEncoding) -> String {
+    let hex = clean_hex(hex);
+    match encoding {
+        GraphicEncoding::Hex => hex,
+        GraphicEncoding::Acs => acs_encode(&hex, bytes_per_row),
+        GraphicEncoding::Z64 => z64_encode(&hex_to_bytes(&hex)),
+        GraphicEncoding::B64 => b64_encode(&hex_to_bytes(&hex)),
+        GraphicEncoding::Auto => [
+            GraphicEncoding::Hex,
+            GraphicEncoding::Acs,
+            GraphicEncoding::Z64,
+            GraphicEncoding::B64,
+        ]
+        .into_iter()
+        .map(|encoding| encode_graphic_data(&hex, bytes_per_row, encoding))
+        .min_by_key(|encoded| encoded.len())
+        .unwrap_or_default(),
+    }
+}
+
+pub fn decode_graphic_data(
+    data: &str,
+    bytes_per_row: u32,
+) -> Result<(String, GraphicEncoding), String> {
+    let data: String = data.split_whitespace().collect();
+    if let Some(payload) = data.strip_prefix(":Z64:") {
+        let compressed = base64_payload(payload)?;
+        let mut bytes = Vec::new();
+        ZlibDecoder::new(compressed.as_slice())
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("Invalid Z64 graphic data: {}", e))?;
+        Ok((bytes_to_hex(&bytes), GraphicEncoding::Z64))
+    } else if let Some(payload) = data.strip_prefix(":B64:") {
+        let bytes = base64_payload(payload)?;
+        Ok((bytes_to_hex(&bytes), GraphicEncoding::B64))
+    } else if data.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok((data.to_uppercase(), GraphicEncoding::Hex))
+    } else {
+        Ok((acs_decode(&data, bytes_per_row)?, GraphicEncoding::Acs))
+    }
+}
+
+pub fn decode_graphic_payload(
+    format: char,
+    byte_count: u32,
+    bytes_per_row: u32,
+    payload: &[u8],
+) -> Result<(String, GraphicEncoding), String> {
+    let text = String::from_utf8_lossy(payload);
+    match format.to_ascii_uppercase() {
+        'A' => decode_graphic_data(&text, bytes_per_row),
+        'B' | 'C' if text.trim_start().starts_with(':') => {
+            decode_graphic_data(&text, bytes_per_row)
+        }
+        'B' => {
+            let length = payload.len().min(byte_count as usize);
+            Ok((bytes_to_hex(&payload[..length]), GraphicEncoding::Auto))
+        }
+        'C' => Err(
+            "Compressed binary (C) graphic data is only supported with Z64 or B64 encoding"
+                .to_string(),
+        ),
+        other => Err(format!("Invalid graphic format '{}'", other)),
+    }
+}
+
+pub fn trimmed_graphic_width(bytes_per_row: u32, hex: &str) -> u32 {
+    let full_width = bytes_per_row * 8;
+    if bytes_per_row == 0 {
+        return 0;
+    }
+    let last_column = hex_to_bytes(&clean_hex(hex))
+        .iter()
+        .skip(bytes_per_row as usize - 1)
+        .step_by(bytes_per_row as usize)
+        .fold(0u8, |bits, byte| bits | byte);
+    if last_column == 0 {
+        full_width
+    } else {
+        full_width - last_column.trailing_zeros()
+    }
+}
+
+pub fn graphic_to_image(width: u32, height: u32, hex: &str) -> DynamicImage {
+    let bytes_per_row = width.div_ceil(8) as usize;
+    let bytes = hex_to_bytes(&clean_hex(hex));
+    let image = GrayImage::from_fn(width, height, |x, y| {
+        let byte = bytes
+            .get(y as usize * bytes_per_row + x as usize / 8)
+            .copied()
+            .unwrap_or(0);
+        if byte & (0x80 >> (x % 8)) != 0 {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    });
+    DynamicImage::ImageLuma8(image)
+}
+
+pub fn graphic_command_image(command: &ZplCommand) -> Option<DynamicImage> {
+    match command {
+        ZplCommand::GraphicField {
+            width,
+            height,
+            data,
+            ..
+        }
+        | ZplCommand::DownloadGraphic {
+            width,
+            height,
+            data,
+            ..
+        } => Some(graphic_to_image(*width, *height, data)),
+        _ => None,
+    }
+}
+
+pub fn extract_graphics(prn: &[u8]) -> Result<Vec<ZplCommand>, String> {
+    let mut graphics = Vec::new();
+    let mut position = 0;
+
+    while position + 3 <= prn.len() {
+        let prefix = prn[position];
+        let code = [
+            prn[position + 1].to_ascii_uppercase(),
+            prn[position + 2].to_ascii_uppercase(),
+        ];
+        let offset = position;
+        let error = |message: String| {
+            format!(
+                "{}{} at offset {}: {}",
+                prefix as char,
+                String::from_utf8_lossy(&code),
+                offset,
+                message
+            )
+        };
+
+        match (prefix, &code) {
+            (b'^', b"GF") => {
+                let (fields, data_start) = header_fields(prn, position + 3, 4);
+                if fields.len() < 4 {
+                    return Err(error("missing graphic parameters".to_string()));
+                }
+                let format = fields[0].chars().next().unwrap_or('A');
+                let byte_count = header_number(&fields[1]).map_err(&error)?;
+                let total_bytes = header_number(&fields[2]).map_err(&error)?;
+                let bytes_per_row = header_number(&fields[3]).map_err(&error)?;
+                let raw_binary =
+                    format.eq_ignore_ascii_case(&'B') && prn.get(data_start) != Some(&b':');
+                let data_end = if raw_binary {
+                    prn.len().min(data_start + byte_count as usize)
+                } else {
+                    command_end(prn, data_start)
+                };
+                let (data, encoding) = decode_graphic_payload(
+                    format,
+                    byte_count,
+                    bytes_per_row,
+                    &prn[data_start..data_end],
+                )
+                .map_err(&error)?;
+                let height = total_bytes
+                    .checked_div(bytes_per_row)
+                    .ok_or_else(|| error("bytes per row must be greater than zero".to_string()))?;
+                graphics.push(ZplCommand::GraphicField {
+                    width: bytes_per_row * 8,
+                    height,
+                    data,
+                    encoding,
+                });
+                position = data_end;
+            }
+            (b'~', b"DG") => {
+                let (fields, data_start) = header_fields(prn, position + 3, 3);
+                if fields.len() < 3 {
+                    return Err(error("missing graphic parameters".to_string()));
+                }
+                let total_bytes = header_number(&fields[1]).map_err(&error)?;
+                let bytes_per_row = header_number(&fields[2]).map_err(&error)?;
+                let data_end = command_end(prn, data_start);
+                let (data, encoding) = decode_graphic_payload(
+                    'A',
+                    total_bytes,
+                    bytes_per_row,
+                    &prn[data_start..data_end],
+                )
+                .map_err(&error)?;
+                let height = total_bytes
+                    .checked_div(bytes_per_row)
+                    .ok_or_else(|| error("bytes per row must be greater than zero".to_string()))?;
+                graphics.push(ZplCommand::DownloadGraphic {
+                    name: fields[0].clone(),
+                    width: bytes_per_row * 8,
+                    height,
+                    data,
+                    encoding,
+                });
+                position = data_end;
+            }
+            _ => position += 1,
+        }
+    }
+
+    Ok(graphics)
+}
+
+fn header_fields(prn: &[u8], start: usize, count: usize) -> (Vec<String>, usize) {
+    let mut fields = Vec::new();
+    let mut position = start;
+    let mut field = Vec::new();
+
+    while fields.len() < count {
+        match prn.get(position) {
+            Some(b',') => fields.push(std::mem::take(&mut field)),
+            Some(b'^' | b'~') | None => break,
+            Some(byte) => field.push(*byte),
+        }
+        position += 1;
+    }
+
+    let fields = fields
+        .iter()
+        .map(|field| String::from_utf8_lossy(field).trim().to_string())
+        .collect();
+    (fields, position)
+}
+
+fn header_number(field: &str) -> Result<u32, String> {
+    field
+        .parse()
+        .map_err(|_| format!("invalid number '{}'", field))
+}
+
+fn command_end(prn: &[u8], start: usize) -> usize {
+    prn[start.min(prn.len())..]
+        .iter()
+        .position(|byte| *byte == b'^' || *byte == b'~')
+        .map_or(prn.len(), |length| start + length)
+}
+
+pub fn acs_encode(hex: &str, bytes_per_row: u32) -> String {
+    let row_length = (bytes_per_row * 2) as usize;
+    if row_length == 0 {
+        return String::new();
+    }
+
+    let mut encoded = String::new();
+    let mut previous: Option<&str> = None;
+    for row in hex.as_bytes().chunks(row_length) {
+        let row = std::str::from_utf8(row).unwrap_or_default();
+        if previous == Some(row) && row.len() == row_length {
+            encoded.push(':');
+            continue;
+        }
+        previous = Some(row);
+
+        let (body, fill) = match (row.trim_end_matches('0'), row.trim_end_matches('F')) {
+            (zeros, _) if zeros.len() < row.len() && row.len() == row_length => (zeros, Some(',')),
+            (_, ones) if ones.len() < row.len() && row.len() == row_length => (ones, Some('!')),
+            _ => (row, None),
+        };
+
+        let mut chars = body.chars().peekable();
+        while let Some(character) = chars.next() {
+            let mut count = 1;
+            while chars.peek() == Some(&character) {
+                chars.next();
+                count += 1;
+            }
+            encoded.push_str(&acs_repeat_count(count));
+            encoded.push(character);
+        }
+        if let Some(fill) = fill {
+            encoded.push(fill);
+        }
+    }
+    encoded
+}
+
+fn acs_repeat_count(mut count: usize) -> String {
+    let mut code = String::new();
+    if count <= 1 {
+        return code;
+    }
+    while count >= 400 {
+        code.push('z');
+        count -= 400;
+    }
+    if count >= 20 {
+        code.push((b'f' + (count / 20) as u8) as char);
+        count %= 20;
+    }
+    if count > 0 {
+        code.push((b'F' + count as u8) as char);
+    }
+    code
+}
+
+pub fn acs_decode(data: &str, bytes_per_row: u32) -> Result<String, String> {
+    let row_length = (bytes_per_row * 2) as usize;
+    if row_length == 0 {
+        return Err("Bytes per row must be greater than zero".to_string());
+    }
+
+    let mut rows: Vec<String> = Vec::new();
+    let mut row = String::with_capacity(row_length);
+    let mut count = 0;
+
+    for character in data.chars() {
+        match character {
+            'G'..='Y' => count += (character as u8 - b'F') as usize,
+            'g'..='z' => count += (character as u8 - b'f') as usize * 20,
+            ',' => finish_row(&mut rows, &mut row, '0', row_length),
+            '!' => finish_row(&mut rows, &mut row, 'F', row_length),
+            ':' => {
+                if row.is_empty() {
+                    row = rows
+                        .last()
+                        .cloned()
+                        .unwrap_or_else(|| "0".repeat(row_length));
+                }
+                finish_row(&mut rows, &mut row, '0', row_length);
+            }
+            c if c.is_ascii_hexdigit() => {
+                for _ in 0..count.max(1) {
+                    row.push(c.to_ascii_uppercase());
+                    if row.len() == row_length {
+                        finish_row(&mut rows, &mut row, '0', row_length);
+                    }
+                }
+                count = 0;
+            }
+            other => return Err(format!("Invalid character '{}' in graphic data", other)),
+        }
+    }
+    if !row.is_empty() {
+        finish_row(&mut rows, &mut row, '0', row_length);
+    }
+
+    Ok(rows.concat())
+}
+
+fn finish_row(rows: &mut Vec<String>, row: &mut String, fill: char, row_length: usize) {
+    while row.len() < row_length {
+        row.push(fill);
+    }
+    rows.push(std::mem::take(row));
+}
+
+pub fn z64_encode(bytes: &[u8]) -> String {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    let compressed = encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .unwrap_or_default();
+    let payload = STANDARD.encode(compressed);
+    format!(":Z64:{}:{:04x}", payload, crc16(payload.as_bytes()))
+}
+
+pub fn b64_encode(bytes: &[u8]) -> String {
+    let payload = STANDARD.encode(bytes);
+    format!(":B64:{}:{:04x}", payload, crc16(payload.as_bytes()))
+}
+
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+fn base64_payload(payload: &str) -> Result<Vec<u8>, String> {
+    let (encoded, crc) = match payload.rsplit_once(':') {
+        Some((encoded, crc)) => (encoded, Some(crc)),
+        None => (payload, None),
+    };
+    if let Some(crc) = crc {
+        let expected =
+            u16::from_str_radix(crc, 16).map_err(|_| format!("Invalid graphic CRC '{}'", crc))?;
+        let actual = crc16(encoded.as_bytes());
+        if expected != actual {
+            return Err(format!(
+                "Graphic CRC mismatch: expected {:04x}, got {:04x}",
+                expected, actual
+            ));
+        }
+    }
+    STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("Invalid base64 graphic data: {}", e))
+}
+
+fn clean_hex(data: &str) -> String {
+    data.chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    hex.as_bytes()
+        .chunks(2)
+        .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: &str = "FFFF0000FFFF0000F0F0F0F0F0F0F0F000000000000000000000000000000000";
+
+    #[test]
+    fn acs_round_trips() {
+        let encoded = acs_encode(ROWS, 8);
+        assert!(encoded.len() < ROWS.len());
+        assert_eq!(acs_decode(&encoded, 8).unwrap(), ROWS);
+    }
+
+    #[test]
+    fn z64_and_b64_round_trip() {
+        for encoding in [GraphicEncoding::Z64, GraphicEncoding::B64] {
+            let encoded = encode_graphic_data(ROWS, 8, encoding);
+            assert_eq!(
+                decode_graphic_data(&encoded, 8).unwrap(),
+                (ROWS.to_string(), encoding)
+            );
+        }
+    }
+
+    #[test]
+    fn z64_crc_mismatch_is_rejected() {
+        let encoded = encode_graphic_data(ROWS, 8, GraphicEncoding::Z64);
+        let corrupted = format!("{}0000", &encoded[..encoded.len() - 4]);
+        assert!(decode_graphic_data(&corrupted, 8).is_err());
+    }
+
+    #[test]
+    fn extracted_width_is_a_whole_number_of_bytes() {
+        let graphics = extract_graphics(b"^XA^FO0,0^GFA,4,4,2,FFF0FFF0^FS^XZ").unwrap();
+        assert!(matches!(
+            graphics[0],
+            ZplCommand::GraphicField {
+                width: 16,
+                height: 2,
+                ..
+            }
+        ));
+        assert_eq!(trimmed_graphic_width(2, "FFF0FFF0"), 12);
+    }
+
+    #[test]
+    fn crc16_matches_xmodem() {
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+    }
+}
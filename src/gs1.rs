@@ -0,0 +1,380 @@
+use crate::barcode::{code128_invocation_data, mod10_check_digit};
+use crate::zpl::{Code128Mode, FieldOrientation, FontName, FontOrientation, ZplCommand};
+use std::fmt;
+
+const INTERPRETATION_HEIGHT: u32 = 25;
+const INTERPRETATION_GAP: u32 = 8;
+
+const PREDEFINED_LENGTH_PREFIXES: [&str; 22] = [
+    "00", "01", "02", "03", "04", "11", "12", "13", "14", "15", "16", "17", "18", "19", "20", "31",
+    "32", "33", "34", "35", "36", "41",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Gs1Content {
+    Numeric,
+    Alphanumeric,
+    Date,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Gs1AiFormat {
+    min_length: usize,
+    max_length: usize,
+    content: Gs1Content,
+    check_digit: bool,
+}
+
+impl Gs1AiFormat {
+    const fn fixed(length: usize, content: Gs1Content, check_digit: bool) -> Self {
+        Self {
+            min_length: length,
+            max_length: length,
+            content,
+            check_digit,
+        }
+    }
+
+    const fn variable(max_length: usize, content: Gs1Content) -> Self {
+        Self {
+            min_length: 1,
+            max_length,
+            content,
+            check_digit: false,
+        }
+    }
+}
+
+fn ai_format(ai: &str) -> Option<Gs1AiFormat> {
+    use Gs1Content::*;
+
+    let format = match ai {
+        "00" => Gs1AiFormat::fixed(18, Numeric, true),
+        "01" | "02" => Gs1AiFormat::fixed(14, Numeric, true),
+        "10" | "21" | "22" => Gs1AiFormat::variable(20, Alphanumeric),
+        "11" | "12" | "13" | "15" | "16" | "17" => Gs1AiFormat::fixed(6, Date, false),
+        "20" => Gs1AiFormat::fixed(2, Numeric, false),
+        "30" | "37" => Gs1AiFormat::variable(8, Numeric),
+        "240" | "241" | "400" | "401" | "403" => Gs1AiFormat::variable(30, Alphanumeric),
+        "402" => Gs1AiFormat::fixed(17, Numeric, true),
+        "410" | "411" | "412" | "413" | "414" | "415" => Gs1AiFormat::fixed(13, Numeric, true),
+        "420" => Gs1AiFormat::variable(20, Alphanumeric),
+        "90" => Gs1AiFormat::variable(30, Alphanumeric),
+        "91" | "92" | "93" | "94" | "95" | "96" | "97" | "98" | "99" => {
+            Gs1AiFormat::variable(90, Alphanumeric)
+        }
+        _ if ai.len() == 4
+            && ["31", "32", "33", "34", "35", "36"].contains(&&ai[..2])
+            && ai.bytes().all(|byte| byte.is_ascii_digit())
+            && ai.as_bytes()[3] <= b'5' =>
+        {
+            Gs1AiFormat::fixed(6, Numeric, false)
+        }
+        _ => return None,
+    };
+    Some(format)
+}
+
+fn is_gs1_character(character: char) -> bool {
+    character.is_ascii_alphanumeric() || "!\"%&'()*+,-./:;<=>?_".contains(character)
+}
+
+fn has_predefined_length(ai: &str) -> bool {
+    PREDEFINED_LENGTH_PREFIXES
+        .iter()
+        .any(|prefix| ai.starts_with(prefix))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Gs1Error {
+    Empty,
+    UnknownAi(String),
+    InvalidLength {
+        ai: String,
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
+    InvalidCharacter {
+        ai: String,
+        position: usize,
+        character: char,
+    },
+    InvalidCheckDigit {
+        ai: String,
+        expected: char,
+        actual: char,
+    },
+    InvalidDate {
+        ai: String,
+        value: String,
+    },
+    Encoding(String),
+}
+
+impl fmt::Display for Gs1Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Gs1Error::Empty => write!(f, "GS1-128 barcode has no elements"),
+            Gs1Error::UnknownAi(ai) => write!(f, "Unknown application identifier ({})", ai),
+            Gs1Error::InvalidLength {
+                ai,
+                min,
+                max,
+                actual,
+            } if min == max => write!(f, "({}) must be {} characters, got {}", ai, min, actual),
+            Gs1Error::InvalidLength {
+                ai,
+                min,
+                max,
+                actual,
+            } => write!(
+                f,
+                "({}) must be {} to {} characters, got {}",
+                ai, min, max, actual
+            ),
+            Gs1Error::InvalidCharacter {
+                ai,
+                position,
+                character,
+            } => write!(
+                f,
+                "({}) has invalid character '{}' at position {}",
+                ai,
+                character,
+                position + 1
+            ),
+            Gs1Error::InvalidCheckDigit {
+                ai,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "({}) check digit mismatch: expected {}, got {}",
+                ai, expected, actual
+            ),
+            Gs1Error::InvalidDate { ai, value } => {
+                write!(f, "({}) is not a valid YYMMDD date: {}", ai, value)
+            }
+            Gs1Error::Encoding(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for Gs1Error {}
+
+impl From<Gs1Error> for String {
+    fn from(error: Gs1Error) -> Self {
+        error.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gs1Element {
+    pub ai: String,
+    pub value: String,
+}
+
+impl Gs1Element {
+    pub fn validate(&self) -> Result<(), Gs1Error> {
+        let format = ai_format(&self.ai).ok_or_else(|| Gs1Error::UnknownAi(self.ai.clone()))?;
+        let length = self.value.chars().count();
+        if length < format.min_length || length > format.max_length {
+            return Err(Gs1Error::InvalidLength {
+                ai: self.ai.clone(),
+                min: format.min_length,
+                max: format.max_length,
+                actual: length,
+            });
+        }
+
+        let numeric = format.content != Gs1Content::Alphanumeric;
+        if let Some((position, character)) =
+            self.value.chars().enumerate().find(|(_, character)| {
+                if numeric {
+                    !character.is_ascii_digit()
+                } else {
+                    !is_gs1_character(*character)
+                }
+            })
+        {
+            return Err(Gs1Error::InvalidCharacter {
+                ai: self.ai.clone(),
+                position,
+                character,
+            });
+        }
+
+        if format.check_digit {
+            let (payload, actual) = self.value.split_at(self.value.len() - 1);
+            let expected = (b'0' + mod10_check_digit(payload).unwrap_or(0)) as char;
+            let actual = actual.chars().next().unwrap_or_default();
+            if expected != actual {
+                return Err(Gs1Error::InvalidCheckDigit {
+                    ai: self.ai.clone(),
+                    expected,
+                    actual,
+                });
+            }
+        }
+
+        if format.content == Gs1Content::Date {
+            let month: u32 = self.value[2..4].parse().unwrap_or(0);
+            let day: u32 = self.value[4..6].parse().unwrap_or(0);
+            if !(1..=12).contains(&month) || day > 31 {
+                return Err(Gs1Error::InvalidDate {
+                    ai: self.ai.clone(),
+                    value: self.value.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gs1Barcode {
+    elements: Vec<Gs1Element>,
+}
+
+impl Gs1Barcode {
+    pub fn new(elements: &[(&str, &str)]) -> Result<Self, Gs1Error> {
+        if elements.is_empty() {
+            return Err(Gs1Error::Empty);
+        }
+        let elements: Vec<Gs1Element> = elements
+            .iter()
+            .map(|(ai, value)| Gs1Element {
+                ai: ai.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+        for element in &elements {
+            element.validate()?;
+        }
+        Ok(Self { elements })
+    }
+
+    pub fn elements(&self) -> &[Gs1Element] {
+        &self.elements
+    }
+
+    pub fn field_data(&self) -> Result<String, Gs1Error> {
+        let mut data = String::from(">8");
+        for (index, element) in self.elements.iter().enumerate() {
+            data.push_str(&element.ai);
+            data.push_str(&element.value.replace('>', "><"));
+            if index + 1 < self.elements.len() && !has_predefined_length(&element.ai) {
+                data.push_str(">8");
+            }
+        }
+        code128_invocation_data(&data).map_err(Gs1Error::Encoding)
+    }
+
+    pub fn human_readable(&self) -> String {
+        self.elements
+            .iter()
+            .map(|element| format!("({}){}", element.ai, element.value))
+            .collect()
+    }
+
+    pub fn to_commands(
+        &self,
+        x: u32,
+        y: u32,
+        height: u32,
+        interpretation: bool,
+    ) -> Result<Vec<ZplCommand>, Gs1Error> {
+        let mut commands = vec![
+            ZplCommand::FieldOrigin { x, y },
+            ZplCommand::Code128Barcode {
+                orientation: FieldOrientation::Normal,
+                height,
+                print_interpretation: false,
+                print_above: false,
+                check_digit: false,
+                mode: Code128Mode::NoSelection,
+            },
+            ZplCommand::FieldData {
+                data: self.field_data()?,
+            },
+            ZplCommand::FieldSeparator,
+        ];
+        if interpretation {
+            commands.extend([
+                ZplCommand::FieldOrigin {
+                    x,
+                    y: y + height + INTERPRETATION_GAP,
+                },
+                ZplCommand::Font {
+                    font: FontName::default(),
+                    orientation: FontOrientation::Normal,
+                    height: INTERPRETATION_HEIGHT,
+                    width: INTERPRETATION_HEIGHT,
+                },
+                ZplCommand::FieldData {
+                    data: self.human_readable(),
+                },
+                ZplCommand::FieldSeparator,
+            ]);
+        }
+        Ok(commands)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnc1_follows_variable_length_elements_only() {
+        let barcode =
+            Gs1Barcode::new(&[("01", "09501101530003"), ("10", "LOT42"), ("21", "7")]).unwrap();
+        let data = barcode.field_data().unwrap();
+        assert_eq!(data, ">;>8010950110153000310>6LOT42>8217");
+
+        let barcode = Gs1Barcode::new(&[("17", "250101"), ("10", "LOT42")]).unwrap();
+        assert!(!barcode.field_data().unwrap().ends_with(">8"));
+    }
+
+    #[test]
+    fn gtin_check_digit_is_validated() {
+        assert!(Gs1Barcode::new(&[("01", "09501101530003")]).is_ok());
+        assert_eq!(
+            Gs1Barcode::new(&[("01", "09501101530004")]),
+            Err(Gs1Error::InvalidCheckDigit {
+                ai: "01".to_string(),
+                expected: '3',
+                actual: '4',
+            })
+        );
+    }
+
+    #[test]
+    fn date_elements_must_be_yymmdd() {
+        assert!(Gs1Barcode::new(&[("17", "250131")]).is_ok());
+        assert!(Gs1Barcode::new(&[("17", "250100")]).is_ok());
+        for value in ["251301", "250132", "2501AA"] {
+            assert!(Gs1Barcode::new(&[("17", value)]).is_err(), "{}", value);
+        }
+        assert!(matches!(
+            Gs1Barcode::new(&[("17", "2501")]),
+            Err(Gs1Error::InvalidLength { .. })
+        ));
+    }
+
+    #[test]
+    fn interpretation_is_printed_under_the_symbol() {
+        let barcode = Gs1Barcode::new(&[("01", "09501101530003"), ("17", "250101")]).unwrap();
+        let commands = barcode.to_commands(50, 40, 100, true).unwrap();
+        assert_eq!(commands[4], ZplCommand::FieldOrigin { x: 50, y: 148 });
+        assert_eq!(
+            commands[6],
+            ZplCommand::FieldData {
+                data: "(01)09501101530003(17)250101".to_string()
+            }
+        );
+        assert_eq!(barcode.to_commands(50, 40, 100, false).unwrap().len(), 4);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::zpl::{ZplCommand, dots_to_inches, label_dimensions};
+
 #[cfg(not(target_arch = "wasm32"))]
 use reqwest::blocking;
 
@@ -21,6 +23,16 @@ impl LabelaryClient {
         }
     }
 
+    pub fn for_commands(dpmm: u8, commands: &[ZplCommand]) -> Self {
+        let defaults = Self::default();
+        let (width, length) = label_dimensions(commands);
+        Self::new(
+            dpmm,
+            width.map_or(defaults.width, |dots| dots_to_inches(dots, dpmm)),
+            length.map_or(defaults.height, |dots| dots_to_inches(dots, dpmm)),
+        )
+    }
+
     fn get_url(&self) -> String {
         format!(
             "{}/{}dpmm/labels/{}x{}/0/",
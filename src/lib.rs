@@ -1,11 +1,37 @@
 extern crate alloc;
 
+pub mod barcode;
+pub mod clock;
+pub mod font;
+pub mod graphic;
+pub mod gs1;
 pub mod labelary;
+pub mod lint;
+pub mod parser;
 pub mod printer;
 pub mod printer_status;
+pub mod renderer;
+pub mod serial;
+pub mod stored_format;
+pub mod template;
+pub mod text;
+pub mod units;
 pub mod zpl;
 
+pub use barcode::*;
+pub use clock::*;
+pub use font::*;
+pub use graphic::*;
+pub use gs1::*;
 pub use labelary::*;
+pub use lint::*;
+pub use parser::*;
 pub use printer::*;
 pub use printer_status::*;
+pub use renderer::*;
+pub use serial::*;
+pub use stored_format::*;
+pub use template::*;
+pub use text::*;
+pub use units::*;
 pub use zpl::*;
@@ -0,0 +1,355 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::barcode::validate_label_barcodes;
+use crate::parser::{parse_zpl, tokenize_zpl};
+use crate::renderer::LabelRenderer;
+use crate::zpl::{ZplCommand, ZplPrefix};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Info => "Info",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label().to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub index: usize,
+    pub offset: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, index: usize, message: impl Into<String>) -> Self {
+        Self {
+            severity,
+            index,
+            offset: None,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} in command #{}: {}",
+            self.severity,
+            self.index + 1,
+            self.message
+        )
+    }
+}
+
+impl From<Diagnostic> for String {
+    fn from(diagnostic: Diagnostic) -> Self {
+        diagnostic.to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+struct FieldState {
+    origin: Option<usize>,
+    number: bool,
+    content: Option<usize>,
+}
+
+pub fn lint_commands(commands: &[ZplCommand]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let renderer = LabelRenderer::for_commands(8, commands);
+    let (label_width, label_length) = (renderer.width_dots(), renderer.height_dots());
+
+    let mut format_start: Option<usize> = None;
+    let mut field = FieldState::default();
+    let mut label_home = (0, 0);
+    let mut encoding = None;
+
+    for (index, command) in commands.iter().enumerate() {
+        if let ZplCommand::GraphicField {
+            width,
+            height,
+            data,
+            ..
+        }
+        | ZplCommand::DownloadGraphic {
+            width,
+            height,
+            data,
+            ..
+        } = command
+        {
+            let expected = width.div_ceil(8) as usize * *height as usize;
+            let actual = data.chars().filter(|c| c.is_ascii_hexdigit()).count() / 2;
+            if actual != expected {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    index,
+                    format!(
+                        "Graphic data has {} bytes but a {}x{} graphic needs {}",
+                        actual, width, height, expected
+                    ),
+                ));
+            }
+        }
+
+        match command {
+            ZplCommand::StartFormat => {
+                if let Some(start) = format_start {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        index,
+                        format!(
+                            "^XA inside the format opened at command #{}; ^XZ is missing",
+                            start + 1
+                        ),
+                    ));
+                }
+                format_start = Some(index);
+                field = FieldState::default();
+                label_home = (0, 0);
+                continue;
+            }
+            ZplCommand::EndFormat => {
+                if format_start.is_none() {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        index,
+                        "^XZ without a matching ^XA",
+                    ));
+                }
+                unclosed_field(&mut diagnostics, &field, index);
+                format_start = None;
+                field = FieldState::default();
+                continue;
+            }
+            _ => {}
+        }
+
+        if matches!(
+            command,
+            ZplCommand::SetDarkness { .. }
+                | ZplCommand::DownloadGraphic { .. }
+                | ZplCommand::DownloadFont { .. }
+                | ZplCommand::DelayedCut
+                | ZplCommand::Comment { .. }
+                | ZplCommand::Raw {
+                    prefix: ZplPrefix::Tilde,
+                    ..
+                }
+        ) {
+            continue;
+        }
+        if format_start.is_none() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                index,
+                format!("{} is outside ^XA...^XZ", command.command_name()),
+            ));
+        }
+
+        match command {
+            ZplCommand::LabelHome { x, y } => label_home = (*x, *y),
+            ZplCommand::FieldOrigin { x, y } | ZplCommand::FieldTypeset { x, y, .. } => {
+                unclosed_field(&mut diagnostics, &field, index);
+                field = FieldState {
+                    origin: Some(index),
+                    ..FieldState::default()
+                };
+                let (x, y) = (x + label_home.0, y + label_home.1);
+                if x >= label_width || y >= label_length {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        index,
+                        format!(
+                            "Field at ({}, {}) is outside the {}x{} dot label",
+                            x, y, label_width, label_length
+                        ),
+                    ));
+                }
+            }
+            ZplCommand::ChangeEncoding { encoding: selected } => encoding = Some(*selected),
+            ZplCommand::DataMatrix { .. } | ZplCommand::Pdf417 { .. } => diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    index,
+                    format!(
+                        "{} is not rendered locally; the preview shows a placeholder of its estimated size",
+                        command.command_name()
+                    ),
+                ),
+            ),
+            ZplCommand::FieldNumber { .. } => field.number = true,
+            ZplCommand::DownloadFormat { .. } | ZplCommand::RecallFormat { .. } => {
+                field.content = Some(index)
+            }
+            ZplCommand::FieldData { .. } | ZplCommand::SerialNumber { .. } => {
+                if let ZplCommand::FieldData { data } = command
+                    && !data.is_ascii()
+                    && encoding != Some(28)
+                {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        index,
+                        "Non-ASCII field data is sent as UTF-8 but ^CI28 is not selected",
+                    ));
+                }
+                if field.origin.is_none() && !field.number {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Error,
+                        index,
+                        "Field data without a field origin (^FO)",
+                    ));
+                }
+                field.content = Some(index);
+            }
+            ZplCommand::GraphicBox { .. }
+            | ZplCommand::GraphicCircle { .. }
+            | ZplCommand::GraphicEllipse { .. }
+            | ZplCommand::GraphicDiagonalLine { .. }
+            | ZplCommand::GraphicField { .. }
+            | ZplCommand::RecallGraphic { .. } => {
+                if field.origin.is_none() {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Warning,
+                        index,
+                        format!(
+                            "{} without a field origin (^FO) prints at the label home",
+                            command.command_name()
+                        ),
+                    ));
+                }
+                field.content = Some(index);
+            }
+            ZplCommand::FieldSeparator => {
+                if field.origin.is_none() && field.content.is_none() && !field.number {
+                    diagnostics.push(Diagnostic::new(
+                        Severity::Info,
+                        index,
+                        "^FS without an open field",
+                    ));
+                }
+                field = FieldState::default();
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(start) = format_start {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            commands.len() - 1,
+            format!(
+                "Format opened at command #{} is not closed with ^XZ",
+                start + 1
+            ),
+        ));
+    }
+
+    diagnostics.extend(
+        validate_label_barcodes(commands)
+            .into_iter()
+            .map(|(index, error)| {
+                Diagnostic::new(
+                    Severity::Error,
+                    index,
+                    format!("Invalid barcode data: {}", error),
+                )
+            }),
+    );
+    diagnostics.sort_by_key(|diagnostic| diagnostic.index);
+    diagnostics
+}
+
+fn unclosed_field(diagnostics: &mut Vec<Diagnostic>, field: &FieldState, index: usize) {
+    if let Some(content) = field.content {
+        diagnostics.push(Diagnostic::new(
+            Severity::Error,
+            index,
+            format!("Field at command #{} is not closed with ^FS", content + 1),
+        ));
+    }
+}
+
+pub fn lint_zpl(input: &str) -> Result<Vec<Diagnostic>, String> {
+    let commands = parse_zpl(input)?;
+    let offsets: Vec<usize> = tokenize_zpl(input)
+        .into_iter()
+        .filter(|token| !(token.prefix == ZplPrefix::Caret && token.code == "FH"))
+        .map(|token| token.offset)
+        .collect();
+
+    Ok(lint_commands(&commands)
+        .into_iter()
+        .map(|diagnostic| Diagnostic {
+            offset: offsets.get(diagnostic.index).copied(),
+            ..diagnostic
+        })
+        .collect())
+}
+
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn field_typeset_counts_as_field_origin() {
+        let diagnostics = lint_zpl("^XA^FT50,100^A0N,30,30^FDTypeset^FS^XZ").unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn non_ascii_field_data_needs_utf8_encoding() {
+        let commands = parse_zpl("^XA^FO10,10^FDCafé^FS^XZ").unwrap();
+        let diagnostics = lint_commands(&commands);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].index, 2);
+
+        let commands = parse_zpl("^XA^CI28^FO10,10^FDCafé^FS^XZ").unwrap();
+        assert!(lint_commands(&commands).is_empty());
+    }
+
+    #[test]
+    fn unrendered_symbologies_are_flagged() {
+        let diagnostics = lint_zpl("^XA^FO10,10^BXN,5,200^FDSERIAL123^FS^XZ").unwrap();
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        let oversized = format!("^XA^FO10,10^B7N,10,8^FD{}^FS^XZ", "A".repeat(900));
+        let diagnostics = lint_zpl(&oversized).unwrap();
+        assert!(has_errors(&diagnostics), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn field_data_without_origin_is_an_error() {
+        let diagnostics = lint_zpl("^XA^FDNo origin^FS^XZ").unwrap();
+        assert!(has_errors(&diagnostics));
+        assert_eq!(diagnostics[0].offset, Some(3));
+    }
+}
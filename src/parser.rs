@@ -0,0 +1,824 @@
+use crate::graphic::decode_graphic_data;
+use crate::zpl::{
+    ClockMode, Code128Mode, FieldOrientation, FieldRotation, FontDownload, FontName,
+    FontOrientation, GraphicEncoding, KioskPresentType, MediaMode, MediaTracking, MediaType,
+    QrErrorCorrection, ZplCommand, ZplPrefix, is_font_letter, is_font_path,
+};
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZplToken {
+    pub prefix: ZplPrefix,
+    pub code: String,
+    pub params: String,
+    pub offset: usize,
+}
+
+impl ZplToken {
+    pub fn name(&self) -> String {
+        format!("{}{}", self.prefix, self.code)
+    }
+
+    pub fn split_params(&self) -> Vec<&str> {
+        if self.params.is_empty() {
+            Vec::new()
+        } else {
+            self.params.split(',').collect()
+        }
+    }
+}
+
+fn is_data_command(code: &str) -> bool {
+    matches!(code, "FD" | "FX" | "FV")
+}
+
+pub fn tokenize_zpl(input: &str) -> Vec<ZplToken> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((offset, character)) = chars.next() {
+        let prefix = match character {
+            '^' => ZplPrefix::Caret,
+            '~' => ZplPrefix::Tilde,
+            _ => continue,
+        };
+
+        let mut code = String::new();
+        while code.len() < 2 {
+            match chars.peek() {
+                Some(&(_, next)) if next != '^' && next != '~' => {
+                    code.push(next.to_ascii_uppercase());
+                    chars.next();
+                }
+                _ => break,
+            }
+        }
+
+        let mut params = String::new();
+        while let Some(&(_, next)) = chars.peek() {
+            if next == '^' || next == '~' {
+                break;
+            }
+            if next != '\r' && next != '\n' {
+                params.push(next);
+            }
+            chars.next();
+        }
+
+        if !is_data_command(&code) {
+            params = params.trim().to_string();
+        }
+
+        tokens.push(ZplToken {
+            prefix,
+            code,
+            params,
+            offset,
+        });
+    }
+
+    tokens
+}
+
+pub fn parse_zpl(input: &str) -> Result<Vec<ZplCommand>, String> {
+    let mut commands = Vec::new();
+    let mut hex_indicator = None;
+
+    for token in tokenize_zpl(input) {
+        if token.prefix == ZplPrefix::Caret && token.code == "FH" {
+            hex_indicator = Some(token.params.chars().next().unwrap_or('_'));
+            continue;
+        }
+
+        let mut command = parse_token(&token)?;
+        match &mut command {
+            ZplCommand::FieldData { data } => {
+                if let Some(indicator) = hex_indicator.take() {
+                    *data = decode_field_hex(&token, data, indicator)?;
+                }
+            }
+            ZplCommand::FieldSeparator => hex_indicator = None,
+            _ => {}
+        }
+        commands.push(command);
+    }
+
+    Ok(commands)
+}
+
+fn decode_field_hex(token: &ZplToken, data: &str, indicator: char) -> Result<String, String> {
+    let mut bytes = Vec::with_capacity(data.len());
+    let mut chars = data.chars();
+    while let Some(character) = chars.next() {
+        if character != indicator {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(character.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        let digits: String = chars.by_ref().take(2).collect();
+        let byte = u8::from_str_radix(&digits, 16)
+            .ok()
+            .filter(|_| digits.len() == 2)
+            .ok_or_else(|| {
+                format!(
+                    "{} at offset {}: invalid hex escape '{}{}'",
+                    token.name(),
+                    token.offset,
+                    indicator,
+                    digits
+                )
+            })?;
+        bytes.push(byte);
+    }
+
+    Ok(match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(error) => error.into_bytes().into_iter().map(char::from).collect(),
+    })
+}
+
+pub fn parse_token(token: &ZplToken) -> Result<ZplCommand, String> {
+    let params = token.split_params();
+
+    let command = match (token.prefix, token.code.as_str()) {
+        (ZplPrefix::Caret, "XA") => ZplCommand::StartFormat,
+        (ZplPrefix::Caret, "XZ") => ZplCommand::EndFormat,
+        (ZplPrefix::Caret, "FS") => ZplCommand::FieldSeparator,
+        (ZplPrefix::Caret, "PW") => ZplCommand::PrintWidth {
+            width: param_in_range(token, &params, 0, 812, 2..=32000)?,
+        },
+        (ZplPrefix::Caret, "LL") => ZplCommand::LabelLength {
+            length: param_in_range(token, &params, 0, 1218, 1..=32000)?,
+        },
+        (ZplPrefix::Caret, "LH") => ZplCommand::LabelHome {
+            x: param_in_range(token, &params, 0, 0, 0..=32000)?,
+            y: param_in_range(token, &params, 1, 0, 0..=32000)?,
+        },
+        (ZplPrefix::Caret, "LS") => ZplCommand::LabelShift {
+            shift: param_in_range(token, &params, 0, 0, -9999..=9999)?,
+        },
+        (ZplPrefix::Caret, "LT") => ZplCommand::LabelTop {
+            offset: param_in_range(token, &params, 0, 0, -120..=120)?,
+        },
+        (ZplPrefix::Caret, "PR") => ZplCommand::PrintRate {
+            print_speed: param_speed(token, &params, 0)?.unwrap_or(2),
+            slew_speed: param_speed(token, &params, 1)?,
+            backfeed_speed: param_speed(token, &params, 2)?,
+        },
+        (ZplPrefix::Caret, "MD") => ZplCommand::MediaDarkness {
+            adjustment: param_in_range(token, &params, 0, 0, -30..=30)?,
+        },
+        (ZplPrefix::Tilde, "SD") => ZplCommand::SetDarkness {
+            darkness: param_in_range(token, &params, 0, 15, 0..=30)?,
+        },
+        (ZplPrefix::Caret, "MT") => ZplCommand::MediaType {
+            media_type: param_parsed(token, &params, 0, MediaType::DirectThermal)?,
+        },
+        (ZplPrefix::Caret, "MN") => ZplCommand::MediaTracking {
+            tracking: param_parsed(token, &params, 0, MediaTracking::WebSensing)?,
+        },
+        (ZplPrefix::Caret, "PO") => match param_str(&params, 0).unwrap_or("N") {
+            "N" | "n" => ZplCommand::PrintOrientation { inverted: false },
+            "I" | "i" => ZplCommand::PrintOrientation { inverted: true },
+            other => {
+                return Err(format!(
+                    "{} at offset {}: expected N or I, got '{}'",
+                    token.name(),
+                    token.offset,
+                    other
+                ));
+            }
+        },
+        (ZplPrefix::Caret, "FO") => ZplCommand::FieldOrigin {
+            x: param_u32(token, &params, 0, 0)?,
+            y: param_u32(token, &params, 1, 0)?,
+        },
+        (ZplPrefix::Caret, "FT") => ZplCommand::FieldTypeset {
+            x: param_u32(token, &params, 0, 0)?,
+            y: param_u32(token, &params, 1, 0)?,
+            justification: param_optional_in_range(token, &params, 2, 0..=2)?,
+        },
+        (ZplPrefix::Caret, "A@") => ZplCommand::Font {
+            font: FontName::Device(
+                param_str(&params, 3)
+                    .filter(|path| is_font_path(path))
+                    .ok_or_else(|| {
+                        format!(
+                            "{} at offset {}: missing font path (e.g. E:ARIAL.TTF)",
+                            token.name(),
+                            token.offset
+                        )
+                    })?
+                    .to_string(),
+            ),
+            orientation: param_parsed(token, &params, 0, FontOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 30)?,
+            width: param_u32(token, &params, 2, 30)?,
+        },
+        (ZplPrefix::Caret, code)
+            if let Some(letter) = code.strip_prefix('A').and_then(|rest| rest.chars().next())
+                && is_font_letter(letter) =>
+        {
+            ZplCommand::Font {
+                font: FontName::Letter(letter),
+                orientation: param_parsed(token, &params, 0, FontOrientation::Normal)?,
+                height: param_u32(token, &params, 1, 30)?,
+                width: param_u32(token, &params, 2, 30)?,
+            }
+        }
+        (ZplPrefix::Tilde, "DY")
+            if param_str(&params, 1).is_some_and(|format| format.eq_ignore_ascii_case("A"))
+                && let Some(extension) = param_str(&params, 2).and_then(font_extension) =>
+        {
+            let name = param_str(&params, 0).unwrap_or("R:UNKNOWN");
+            let size = param_u32(token, &params, 3, 0)?;
+            ZplCommand::DownloadFont {
+                path: format!("{}.{}", name, extension),
+                method: FontDownload::TrueType,
+                data: font_payload(token, &params, 5, size)?,
+            }
+        }
+        (ZplPrefix::Tilde, "DU") => {
+            let size = param_u32(token, &params, 1, 0)?;
+            ZplCommand::DownloadFont {
+                path: param_str(&params, 0).unwrap_or("R:UNKNOWN.FNT").to_string(),
+                method: FontDownload::Unbounded,
+                data: font_payload(token, &params, 2, size)?,
+            }
+        }
+        (ZplPrefix::Caret, "CW") => {
+            let alias = param_str(&params, 0)
+                .and_then(|alias| alias.chars().next())
+                .filter(|alias| is_font_letter(*alias))
+                .ok_or_else(|| {
+                    format!(
+                        "{} at offset {}: font alias must be A-Z or 0-9",
+                        token.name(),
+                        token.offset
+                    )
+                })?;
+            let path = param_str(&params, 1)
+                .filter(|path| is_font_path(path))
+                .ok_or_else(|| {
+                    format!(
+                        "{} at offset {}: missing font path (e.g. E:ARIAL.TTF)",
+                        token.name(),
+                        token.offset
+                    )
+                })?;
+            ZplCommand::FontAlias {
+                alias: alias.to_ascii_uppercase(),
+                path: path.to_string(),
+            }
+        }
+        (ZplPrefix::Caret, "FC") => ZplCommand::FieldClock {
+            primary: param_str(&params, 0)
+                .and_then(|value| value.chars().next())
+                .unwrap_or('%'),
+            secondary: param_str(&params, 1).and_then(|value| value.chars().next()),
+            tertiary: param_str(&params, 2).and_then(|value| value.chars().next()),
+        },
+        (ZplPrefix::Caret, "SL") => ZplCommand::ClockMode {
+            mode: param_parsed(token, &params, 0, ClockMode::StartTime)?,
+            language: param_optional_in_range(token, &params, 1, 1..=18)?,
+        },
+        (ZplPrefix::Caret, "ST") => ZplCommand::SetTime {
+            month: param_in_range(token, &params, 0, 1, 1..=12)?,
+            day: param_in_range(token, &params, 1, 1, 1..=31)?,
+            year: param_in_range(token, &params, 2, 2000, 1998..=2097)?,
+            hour: param_in_range(token, &params, 3, 0, 0..=23)?,
+            minute: param_in_range(token, &params, 4, 0, 0..=59)?,
+            second: param_in_range(token, &params, 5, 0, 0..=59)?,
+            twenty_four_hour: match param_str(&params, 6).unwrap_or("M") {
+                "M" | "m" => true,
+                "A" | "a" => false,
+                other => {
+                    return Err(format!(
+                        "{} at offset {}: expected A or M, got '{}'",
+                        token.name(),
+                        token.offset,
+                        other
+                    ));
+                }
+            },
+        },
+        (ZplPrefix::Caret, "SN") => ZplCommand::SerialNumber {
+            start: param_str(&params, 0).unwrap_or("1").to_string(),
+            increment: param_parsed(token, &params, 1, 1)?,
+            leading_zeros: param_bool(token, &params, 2, false)?,
+        },
+        (ZplPrefix::Caret, "SF") => ZplCommand::SerializationField {
+            mask: param_str(&params, 0).unwrap_or("").to_string(),
+            increment: param_str(&params, 1).unwrap_or("1").to_string(),
+        },
+        (ZplPrefix::Caret, "PQ") => ZplCommand::PrintQuantity {
+            quantity: param_in_range(token, &params, 0, 1, 1..=99_999_999)?,
+            pause_count: param_in_range(token, &params, 1, 0, 0..=99_999_999)?,
+            replicates: param_in_range(token, &params, 2, 0, 0..=99_999_999)?,
+            override_pause: param_bool(token, &params, 3, false)?,
+        },
+        (ZplPrefix::Caret, "CI") => ZplCommand::ChangeEncoding {
+            encoding: param_in_range(token, &params, 0, 0, 0..=36)?,
+        },
+        (ZplPrefix::Caret, "FD") => ZplCommand::FieldData {
+            data: token.params.clone(),
+        },
+        (ZplPrefix::Caret, "GB") => ZplCommand::GraphicBox {
+            width: param_u32(token, &params, 0, 1)?,
+            height: param_u32(token, &params, 1, 1)?,
+            thickness: param_u32(token, &params, 2, 1)?,
+            color: param_str(&params, 3).and_then(|color| color.chars().next()),
+            rounding: match param_str(&params, 4) {
+                Some(_) => Some(param_in_range(token, &params, 4, 0, 0..=8)? as u8),
+                None => None,
+            },
+        },
+        (ZplPrefix::Caret, "GC") => ZplCommand::GraphicCircle {
+            diameter: param_u32(token, &params, 0, 3)?,
+            thickness: param_u32(token, &params, 1, 1)?,
+            color: param_str(&params, 2).and_then(|color| color.chars().next()),
+        },
+        (ZplPrefix::Caret, "GE") => ZplCommand::GraphicEllipse {
+            width: param_u32(token, &params, 0, 1)?,
+            height: param_u32(token, &params, 1, 1)?,
+            thickness: param_u32(token, &params, 2, 1)?,
+            color: param_str(&params, 3).and_then(|color| color.chars().next()),
+        },
+        (ZplPrefix::Caret, "GD") => ZplCommand::GraphicDiagonalLine {
+            width: param_u32(token, &params, 0, 1)?,
+            height: param_u32(token, &params, 1, 1)?,
+            thickness: param_u32(token, &params, 2, 1)?,
+            color: param_str(&params, 3).and_then(|color| color.chars().next()),
+            orientation: param_str(&params, 4)
+                .and_then(|orientation| orientation.chars().next())
+                .map(|orientation| orientation.to_ascii_uppercase())
+                .unwrap_or('R'),
+        },
+        (ZplPrefix::Caret, "FR") => ZplCommand::FieldReverse,
+        (ZplPrefix::Caret, "CF") => ZplCommand::ChangeFont {
+            font: param_str(&params, 0).unwrap_or("0").to_string(),
+            size: param_u32(token, &params, 1, 0)?,
+        },
+        (ZplPrefix::Caret, "FW") => ZplCommand::FieldOrientation {
+            rotation: param_parsed(token, &params, 0, FieldRotation::Normal)?,
+        },
+        (ZplPrefix::Caret, "BY") => ZplCommand::BarcodeFieldDefault {
+            width: param_u32(token, &params, 0, 2)?,
+            ratio: param_parsed(token, &params, 1, 3.0)?,
+            height: param_u32(token, &params, 2, 10)?,
+        },
+        (ZplPrefix::Caret, "BC") => ZplCommand::Code128Barcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 10)?,
+            print_interpretation: param_bool(token, &params, 2, true)?,
+            print_above: param_bool(token, &params, 3, false)?,
+            check_digit: param_bool(token, &params, 4, false)?,
+            mode: param_parsed(token, &params, 5, Code128Mode::NoSelection)?,
+        },
+        (ZplPrefix::Caret, "B3") => ZplCommand::Code39Barcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            check_digit: param_bool(token, &params, 1, false)?,
+            height: param_u32(token, &params, 2, 10)?,
+            print_interpretation: param_bool(token, &params, 3, true)?,
+            print_above: param_bool(token, &params, 4, false)?,
+        },
+        (ZplPrefix::Caret, "BE") => ZplCommand::Ean13Barcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 10)?,
+            print_interpretation: param_bool(token, &params, 2, true)?,
+            print_above: param_bool(token, &params, 3, false)?,
+        },
+        (ZplPrefix::Caret, "BU") => ZplCommand::UpcABarcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 10)?,
+            print_interpretation: param_bool(token, &params, 2, true)?,
+            print_above: param_bool(token, &params, 3, false)?,
+            print_check_digit: param_bool(token, &params, 4, true)?,
+        },
+        (ZplPrefix::Caret, "B2") => ZplCommand::Interleaved2of5Barcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 10)?,
+            print_interpretation: param_bool(token, &params, 2, true)?,
+            print_above: param_bool(token, &params, 3, false)?,
+            check_digit: param_bool(token, &params, 4, false)?,
+        },
+        (ZplPrefix::Caret, "B8") => ZplCommand::Ean8Barcode {
+            orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+            height: param_u32(token, &params, 1, 10)?,
+            print_interpretation: param_bool(token, &params, 2, true)?,
+            print_above: param_bool(token, &params, 3, false)?,
+        },
+        (ZplPrefix::Caret, "BQ") => ZplCommand::QrCode {
+            model: param_in_range(token, &params, 1, 2, 1..=2)? as u8,
+            magnification: param_in_range(token, &params, 2, 2, 1..=10)?,
+            error_correction: param_parsed(token, &params, 3, QrErrorCorrection::High)?,
+            mask: param_in_range(token, &params, 4, 7, 0..=7)? as u8,
+        },
+        (ZplPrefix::Caret, "BX") => {
+            let quality = param_u32(token, &params, 2, 0)?;
+            if ![0, 50, 80, 100, 140, 200].contains(&quality) {
+                return Err(format!(
+                    "{} at offset {}: quality must be 0, 50, 80, 100, 140 or 200, got {}",
+                    token.name(),
+                    token.offset,
+                    quality
+                ));
+            }
+            let symbol_range = if quality == 200 { 10..=144 } else { 9..=49 };
+            ZplCommand::DataMatrix {
+                orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+                height: param_in_range(token, &params, 1, 10, 1..=32000)?,
+                quality,
+                columns: param_optional_in_range(token, &params, 3, symbol_range.clone())?,
+                rows: param_optional_in_range(token, &params, 4, symbol_range)?,
+                format_id: param_in_range(token, &params, 5, 6, 0..=6)? as u8,
+                escape: param_str(&params, 6)
+                    .and_then(|escape| escape.chars().next())
+                    .unwrap_or('~'),
+            }
+        }
+        (ZplPrefix::Caret, "B7") => {
+            let columns = param_optional_in_range(token, &params, 3, 1..=30)?;
+            let rows = param_optional_in_range(token, &params, 4, 3..=90)?;
+            if let (Some(columns), Some(rows)) = (columns, rows)
+                && columns * rows > 928
+            {
+                return Err(format!(
+                    "{} at offset {}: columns x rows must not exceed 928, got {}",
+                    token.name(),
+                    token.offset,
+                    columns * rows
+                ));
+            }
+            ZplCommand::Pdf417 {
+                orientation: param_parsed(token, &params, 0, FieldOrientation::Normal)?,
+                row_height: param_in_range(token, &params, 1, 10, 1..=32000)?,
+                security_level: param_in_range(token, &params, 2, 0, 0..=8)? as u8,
+                columns,
+                rows,
+                truncate: param_bool(token, &params, 5, false)?,
+            }
+        }
+        (ZplPrefix::Caret, "GF") => {
+            let format = param_str(&params, 0).unwrap_or("A");
+            let binary = !format.eq_ignore_ascii_case("A");
+            if binary && !param_str(&params, 4).is_some_and(|data| data.starts_with(':')) {
+                return Err(format!(
+                    "{} at offset {}: binary (B/C) graphic fields must be Z64 or B64 encoded; use extract_graphics for raw binary data",
+                    token.name(),
+                    token.offset
+                ));
+            }
+            let total_bytes = param_u32(token, &params, 2, 0)?;
+            let bytes_per_row = param_u32(token, &params, 3, 0)?;
+            let (data, encoding) = graphic_payload(token, &params, 4, bytes_per_row)?;
+            let (width, height) = graphic_dimensions(token, total_bytes, bytes_per_row)?;
+            ZplCommand::GraphicField {
+                width,
+                height,
+                data,
+                encoding,
+            }
+        }
+        (ZplPrefix::Tilde, "DG") => {
+            let name = param_str(&params, 0).unwrap_or("UNKNOWN").to_string();
+            let total_bytes = param_u32(token, &params, 1, 0)?;
+            let bytes_per_row = param_u32(token, &params, 2, 0)?;
+            let (data, encoding) = graphic_payload(token, &params, 3, bytes_per_row)?;
+            let (width, height) = graphic_dimensions(token, total_bytes, bytes_per_row)?;
+            ZplCommand::DownloadGraphic {
+                name,
+                width,
+                height,
+                data,
+                encoding,
+            }
+        }
+        (ZplPrefix::Caret, "XG") => ZplCommand::RecallGraphic {
+            name: param_str(&params, 0).unwrap_or("UNKNOWN").to_string(),
+            magnification_x: param_u32(token, &params, 1, 1)?,
+            magnification_y: param_u32(token, &params, 2, 1)?,
+        },
+        (ZplPrefix::Caret, "DF") => ZplCommand::DownloadFormat {
+            name: param_str(&params, 0).unwrap_or("R:UNKNOWN.ZPL").to_string(),
+        },
+        (ZplPrefix::Caret, "XF") => ZplCommand::RecallFormat {
+            name: param_str(&params, 0).unwrap_or("R:UNKNOWN.ZPL").to_string(),
+        },
+        (ZplPrefix::Caret, "FN") => {
+            let number = token.params.split('"').next().unwrap_or("");
+            ZplCommand::FieldNumber {
+                number: param_in_range(token, &[number], 0, 0, 0..=9999)?,
+            }
+        }
+        (ZplPrefix::Caret, "MM") => ZplCommand::MediaMode {
+            mode: param_parsed(token, &params, 0, MediaMode::TearOff)?,
+            prepeel: param_bool(token, &params, 1, false)?,
+        },
+        (ZplPrefix::Caret, "CN") => ZplCommand::ImmediateCut {
+            cut: param_in_range(token, &params, 0, 1, 0..=1)? == 1,
+        },
+        (ZplPrefix::Tilde, "JK") => ZplCommand::DelayedCut,
+        (ZplPrefix::Caret, "KV") => {
+            let cut_amount = param_in_range(token, &params, 0, 0, 0..=60)?;
+            if (3..10).contains(&cut_amount) {
+                return Err(format!(
+                    "{} at offset {}: cut amount must be 0, 1, 2 or 10-60, got {}",
+                    token.name(),
+                    token.offset,
+                    cut_amount
+                ));
+            }
+            let loop_length = param_in_range(token, &params, 4, 400, 0..=1023)?;
+            if (1..3).contains(&loop_length) {
+                return Err(format!(
+                    "{} at offset {}: loop length must be 0 or 3-1023, got {}",
+                    token.name(),
+                    token.offset,
+                    loop_length
+                ));
+            }
+            ZplCommand::KioskValues {
+                cut_amount,
+                cut_margin: param_in_range(token, &params, 1, 9, 2..=9)?,
+                present_type: param_parsed(token, &params, 2, KioskPresentType::Eject)?,
+                present_timeout: param_in_range(token, &params, 3, 6, 0..=300)?,
+                loop_length,
+            }
+        }
+        (ZplPrefix::Caret, "FB") => ZplCommand::FieldBlock {
+            width: param_u32(token, &params, 0, 0)?,
+            max_lines: param_u32(token, &params, 1, 1)?,
+            line_spacing: param_parsed(token, &params, 2, 0)?,
+            justification: param_str(&params, 3)
+                .and_then(|justification| justification.chars().next())
+                .unwrap_or('L'),
+        },
+        (ZplPrefix::Caret, "FX") => ZplCommand::Comment {
+            text: token.params.clone(),
+        },
+        _ => ZplCommand::Raw {
+            prefix: token.prefix,
+            code: token.code.clone(),
+            params: token.params.clone(),
+        },
+    };
+
+    Ok(command)
+}
+
+fn font_extension(extension: &str) -> Option<&'static str> {
+    match extension.to_ascii_uppercase().as_str() {
+        "T" => Some("TTF"),
+        "E" => Some("TTE"),
+        _ => None,
+    }
+}
+
+fn font_payload(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+    size: u32,
+) -> Result<String, String> {
+    let data: String = param_str(params, index)
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_ascii_hexdigit())
+        .collect();
+    if data.len() / 2 != size as usize {
+        return Err(format!(
+            "{} at offset {}: font data has {} bytes but {} were declared",
+            token.name(),
+            token.offset,
+            data.len() / 2,
+            size
+        ));
+    }
+    Ok(data)
+}
+
+fn param_str<'a>(params: &[&'a str], index: usize) -> Option<&'a str> {
+    params
+        .get(index)
+        .map(|param| param.trim())
+        .filter(|param| !param.is_empty())
+}
+
+fn param_optional<T: FromStr>(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+) -> Result<Option<T>, String> {
+    match param_str(params, index) {
+        Some(value) => value.parse::<T>().map(Some).map_err(|_| {
+            format!(
+                "{} at offset {}: invalid parameter {} '{}'",
+                token.name(),
+                token.offset,
+                index + 1,
+                value
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+fn param_parsed<T: FromStr>(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+    default: T,
+) -> Result<T, String> {
+    Ok(param_optional(token, params, index)?.unwrap_or(default))
+}
+
+fn param_u32(token: &ZplToken, params: &[&str], index: usize, default: u32) -> Result<u32, String> {
+    param_parsed(token, params, index, default)
+}
+
+fn param_in_range<T: FromStr + PartialOrd + fmt::Display>(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+    default: T,
+    range: RangeInclusive<T>,
+) -> Result<T, String> {
+    let value = param_optional_in_range(token, params, index, range)?;
+    Ok(value.unwrap_or(default))
+}
+
+fn param_optional_in_range<T: FromStr + PartialOrd + fmt::Display>(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+    range: RangeInclusive<T>,
+) -> Result<Option<T>, String> {
+    let Some(value) = param_optional::<T>(token, params, index)? else {
+        return Ok(None);
+    };
+    if !range.contains(&value) {
+        return Err(format!(
+            "{} at offset {}: parameter {} must be between {} and {}, got {}",
+            token.name(),
+            token.offset,
+            index + 1,
+            range.start(),
+            range.end(),
+            value
+        ));
+    }
+    Ok(Some(value))
+}
+
+fn param_speed(token: &ZplToken, params: &[&str], index: usize) -> Result<Option<u32>, String> {
+    let speed = match param_str(params, index) {
+        Some("A") | Some("a") => Some(2),
+        Some("B") | Some("b") => Some(3),
+        Some("C") | Some("c") => Some(4),
+        Some("D") | Some("d") => Some(6),
+        Some("E") | Some("e") => Some(8),
+        _ => param_optional_in_range(token, params, index, 1..=14)?,
+    };
+    Ok(speed)
+}
+
+fn param_bool(
+    token: &ZplToken,
+    params: &[&str],
+    index: usize,
+    default: bool,
+) -> Result<bool, String> {
+    match param_str(params, index) {
+        Some("Y") | Some("y") => Ok(true),
+        Some("N") | Some("n") => Ok(false),
+        Some(value) => Err(format!(
+            "{} at offset {}: expected Y or N for parameter {}, got '{}'",
+            token.name(),
+            token.offset,
+            index + 1,
+            value
+        )),
+        None => Ok(default),
+    }
+}
+
+fn graphic_payload(
+    token: &ZplToken,
+    params: &[&str],
+    start: usize,
+    bytes_per_row: u32,
+) -> Result<(String, GraphicEncoding), String> {
+    let data = params.get(start..).unwrap_or_default().join(",");
+    decode_graphic_data(&data, bytes_per_row)
+        .map_err(|error| format!("{} at offset {}: {}", token.name(), token.offset, error))
+}
+
+fn graphic_dimensions(
+    token: &ZplToken,
+    total_bytes: u32,
+    bytes_per_row: u32,
+) -> Result<(u32, u32), String> {
+    match total_bytes.checked_div(bytes_per_row) {
+        Some(height) => Ok((bytes_per_row * 8, height)),
+        None => Err(format!(
+            "{} at offset {}: bytes per row must be greater than zero",
+            token.name(),
+            token.offset
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zpl::commands_to_zpl;
+
+    fn round_trip(zpl: &str) -> Vec<ZplCommand> {
+        let commands = parse_zpl(zpl).unwrap();
+        let reparsed = parse_zpl(&commands_to_zpl(&commands)).unwrap();
+        assert_eq!(reparsed, commands);
+        commands
+    }
+
+    #[test]
+    fn field_data_keeps_commas() {
+        let commands = round_trip("^XA^FO10,10^FDSmith, John, Jr.^FS^XZ");
+        assert_eq!(
+            commands[2],
+            ZplCommand::FieldData {
+                data: "Smith, John, Jr.".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn hex_escaped_field_data_is_decoded() {
+        let commands = round_trip("^XA^CI28^FO10,10^FH^FDCaf_C3_A9 5_5E off^FS^XZ");
+        assert_eq!(
+            commands[3],
+            ZplCommand::FieldData {
+                data: "Café 5^ off".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn multi_line_graphic_field_round_trips() {
+        let commands = round_trip("^XA^FO0,0^GFA,4,4,2,\r\nFF00\r\n00FF\r\n^FS^XZ");
+        let ZplCommand::GraphicField {
+            width,
+            height,
+            data,
+            ..
+        } = &commands[2]
+        else {
+            panic!("expected a graphic field, got {:?}", commands[2]);
+        };
+        assert_eq!((*width, *height), (16, 2));
+        assert_eq!(data.to_uppercase(), "FF0000FF");
+    }
+
+    #[test]
+    fn tilde_commands_round_trip() {
+        let commands = round_trip("~JK~DGR:LOGO.GRF,2,1,FF00^XA^XGR:LOGO.GRF,1,1^FS^XZ");
+        assert_eq!(commands[0], ZplCommand::DelayedCut);
+        assert!(matches!(commands[1], ZplCommand::DownloadGraphic { .. }));
+    }
+
+    #[test]
+    fn unknown_commands_become_raw() {
+        let commands = round_trip("^XA^JUS~JR^XZ");
+        assert_eq!(
+            commands[1],
+            ZplCommand::Raw {
+                prefix: ZplPrefix::Caret,
+                code: "JU".to_string(),
+                params: "S".to_string(),
+            }
+        );
+        assert_eq!(
+            commands[2],
+            ZplCommand::Raw {
+                prefix: ZplPrefix::Tilde,
+                code: "JR".to_string(),
+                params: String::new(),
+            }
+        );
+    }
+    #[test]
+    fn graphic_box_rounding_must_be_in_range() {
+        let commands = round_trip("^XA^FO0,0^GB100,50,2,B,8^FS^XZ");
+        assert!(matches!(
+            commands[2],
+            ZplCommand::GraphicBox {
+                rounding: Some(8),
+                ..
+            }
+        ));
+        assert!(parse_zpl("^XA^FO0,0^GB100,50,2,B,9^FS^XZ").is_err());
+        assert!(parse_zpl("^XA^FO0,0^GB100,50,2,B,300^FS^XZ").is_err());
+    }
+}
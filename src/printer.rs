@@ -1,3 +1,7 @@
+use crate::clock::{local_time, set_time_command};
+use crate::units::Resolution;
+use crate::zpl::{MediaMode, MediaTracking, MediaType, ZplCommand, commands_to_zpl};
+use chrono::NaiveDateTime;
 #[cfg(not(target_arch = "wasm32"))]
 use std::io::{Read, Write};
 #[cfg(not(target_arch = "wasm32"))]
@@ -10,15 +14,74 @@ pub struct ZplPrinter {
     pub name: String,
     pub ip: String,
     pub port: u16,
+    pub profile: PrintProfile,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintProfile {
+    pub print_speed: Option<u32>,
+    pub darkness: Option<u32>,
+    pub media_type: Option<MediaType>,
+    pub media_tracking: Option<MediaTracking>,
+    pub media_mode: MediaMode,
+    pub resolution: Resolution,
+}
+
+impl Default for PrintProfile {
+    fn default() -> Self {
+        Self {
+            print_speed: None,
+            darkness: None,
+            media_type: None,
+            media_tracking: None,
+            media_mode: MediaMode::TearOff,
+            resolution: Resolution::Dpmm8,
+        }
+    }
+}
+
+impl PrintProfile {
+    pub fn to_commands(&self) -> Vec<ZplCommand> {
+        let mut commands = vec![ZplCommand::StartFormat];
+        if let Some(print_speed) = self.print_speed {
+            commands.push(ZplCommand::PrintRate {
+                print_speed,
+                slew_speed: None,
+                backfeed_speed: None,
+            });
+        }
+        if let Some(darkness) = self.darkness {
+            commands.push(ZplCommand::SetDarkness { darkness });
+        }
+        if let Some(media_type) = self.media_type {
+            commands.push(ZplCommand::MediaType { media_type });
+        }
+        if let Some(tracking) = self.media_tracking {
+            commands.push(ZplCommand::MediaTracking { tracking });
+        }
+        commands.push(ZplCommand::MediaMode {
+            mode: self.media_mode,
+            prepeel: false,
+        });
+        commands.push(ZplCommand::EndFormat);
+        commands
+    }
+
+    pub fn to_zpl(&self) -> String {
+        self.to_commands()
+            .iter()
+            .map(|command| command.to_zpl_string())
+            .collect()
+    }
 }
 
-#[cfg(not(target_arch = "wasm32"))]
 impl ZplPrinter {
-    pub fn new(ip: String, port: u16) -> Self {
+    pub fn new(name: String, ip: String, port: u16) -> Self {
         Self {
-            name: format!("ZPL Printer @ {}", ip),
+            name,
             ip,
             port,
+            profile: PrintProfile::default(),
         }
     }
 }
@@ -92,7 +155,7 @@ pub fn query_printer(printer: &ZplPrinter, query: &str) -> Result<String, String
             Ok(0) => break,
             Ok(bytes_read) => {
                 buffer.extend_from_slice(&temp_buffer[..bytes_read]);
-                if buffer.len() > 0 && buffer.contains(&0x03) {
+                if !buffer.is_empty() && buffer.contains(&0x03) {
                     break;
                 }
             }
@@ -123,3 +186,27 @@ pub fn query_printer(printer: &ZplPrinter, query: &str) -> Result<String, String
 pub fn query_printer(_printer: &ZplPrinter, _query: &str) -> Result<String, String> {
     Err("Printer support is not available in WASM".to_string())
 }
+
+pub fn clock_sync_zpl(time: NaiveDateTime) -> String {
+    commands_to_zpl(&[
+        ZplCommand::StartFormat,
+        set_time_command(time),
+        ZplCommand::EndFormat,
+    ])
+}
+
+pub fn sync_printer_clock(printer: &ZplPrinter) -> Result<(), String> {
+    send_to_printer(printer, &clock_sync_zpl(local_time()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_keeps_the_tear_off_preamble() {
+        let printer = ZplPrinter::new("Dock".to_string(), "10.73.27.7".to_string(), 9100);
+        assert_eq!(printer.profile, PrintProfile::default());
+        assert_eq!(printer.profile.to_zpl(), "^XA^MMT^XZ");
+    }
+}
@@ -78,6 +78,12 @@ pub struct MemoryStatus {
     pub current_available_kb: u32,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredFile {
+    pub path: String,
+    pub size: u64,
+}
+
 impl PrinterInfo {
     pub fn parse_serial_number(response: &str) -> Option<String> {
         for line in response.lines() {
@@ -158,7 +164,7 @@ impl PrinterInfo {
         let lines: Vec<&str> = response.lines().collect();
         if lines.len() >= 4 {
             Some(HostStatus {
-                communication_mode: lines.get(0).unwrap_or(&"").trim().to_string(),
+                communication_mode: lines.first().unwrap_or(&"").trim().to_string(),
                 paper_out: lines.get(1).unwrap_or(&"0").trim() == "1",
                 pause: lines.get(2).unwrap_or(&"0").trim() == "1",
                 label_length: lines.get(3).unwrap_or(&"0").trim().to_string(),
@@ -173,7 +179,7 @@ impl PrinterInfo {
         let lines: Vec<&str> = response.lines().collect();
         if !lines.is_empty() {
             Some(SensorMediaStatus {
-                media_type: lines.get(0).unwrap_or(&"Unknown").trim().to_string(),
+                media_type: lines.first().unwrap_or(&"Unknown").trim().to_string(),
                 sensor_profile: lines.get(1).unwrap_or(&"Unknown").trim().to_string(),
                 media_detected: lines.get(2).unwrap_or(&"0").trim() == "1",
                 ribbon_detected: lines.get(3).unwrap_or(&"0").trim() == "1",
@@ -216,7 +222,7 @@ impl PrinterInfo {
     pub fn parse_supplies_status(response: &str) -> Option<SuppliesStatus> {
         let lines: Vec<&str> = response.lines().collect();
         if !lines.is_empty() {
-            let media_status = lines.get(0).unwrap_or(&"Unknown").trim();
+            let media_status = lines.first().unwrap_or(&"Unknown").trim();
             let ribbon_status = lines.get(1).unwrap_or(&"Unknown").trim();
             let percent_str = lines.get(2).unwrap_or(&"");
             let media_percent = percent_str.trim().parse::<u8>().ok();
@@ -289,6 +295,21 @@ impl PrinterInfo {
             None
         }
     }
+
+    pub fn parse_directory_listing(response: &str) -> Vec<StoredFile> {
+        response
+            .lines()
+            .filter_map(|line| {
+                let entry = line
+                    .trim_matches(|c: char| c.is_control() || c.is_whitespace())
+                    .strip_prefix('*')?;
+                let mut parts = entry.split_whitespace();
+                let path = parts.next()?.to_uppercase();
+                let size = parts.next().and_then(|size| size.parse().ok()).unwrap_or(0);
+                Some(StoredFile { path, size })
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
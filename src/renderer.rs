@@ -0,0 +1,1088 @@
+use std::collections::HashMap;
+
+use ab_glyph::{Font, PxScale, ScaleFont, point};
+use chrono::NaiveDateTime;
+use image::{GrayImage, Luma, imageops};
+
+use crate::barcode::{
+    LinearSymbol, MatrixSymbol, code39_symbol, code128_symbol, data_matrix_size, ean8_symbol,
+    ean13_symbol, interleaved_2of5_symbol, pdf417_size, qr_code_symbol, upc_a_symbol,
+};
+use crate::clock::{expand_clock_data, local_time};
+use crate::parser::parse_zpl;
+use crate::serial::{serial_number_value, serial_step, serialize_field};
+use crate::text::{TextStyle, scalable_font, scalable_font_scale, scalable_text_width, wrap_lines};
+use crate::zpl::{
+    FieldOrientation, FieldRotation, FontName, FontOrientation, QrFieldData, ZplCommand,
+    dots_to_inches, inches_to_dots, label_dimensions,
+};
+
+const WHITE: Luma<u8> = Luma([255]);
+const BLACK: Luma<u8> = Luma([0]);
+const INK: Luma<u8> = Luma([255]);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Paint {
+    Solid(Luma<u8>),
+    Reverse,
+}
+
+pub struct LabelRenderer {
+    dpmm: u8,
+    width: f32,
+    height: f32,
+    label_index: u64,
+    clock: Option<NaiveDateTime>,
+}
+
+impl LabelRenderer {
+    pub fn new(dpmm: u8, width: f32, height: f32) -> Self {
+        Self {
+            dpmm,
+            width,
+            height,
+            label_index: 0,
+            clock: None,
+        }
+    }
+
+    pub fn label_index(mut self, label_index: u64) -> Self {
+        self.label_index = label_index;
+        self
+    }
+
+    pub fn clock(mut self, time: NaiveDateTime) -> Self {
+        self.clock = Some(time);
+        self
+    }
+
+    pub fn width_dots(&self) -> u32 {
+        inches_to_dots(self.width, self.dpmm)
+    }
+
+    pub fn height_dots(&self) -> u32 {
+        inches_to_dots(self.height, self.dpmm)
+    }
+
+    pub fn render(&self, commands: &[ZplCommand]) -> GrayImage {
+        let mut canvas = GrayImage::from_pixel(self.width_dots(), self.height_dots(), WHITE);
+        let mut state = RenderState {
+            serial_step: serial_step(commands, self.label_index),
+            clock: self.clock.unwrap_or_else(local_time),
+            ..RenderState::default()
+        };
+
+        for command in commands {
+            state.apply(command, &mut canvas);
+        }
+        state.flush_field(&mut canvas);
+
+        if state.print_inverted {
+            imageops::rotate180_in_place(&mut canvas);
+        }
+        canvas
+    }
+
+    pub fn render_zpl(&self, zpl: &str) -> Result<GrayImage, String> {
+        let commands = parse_zpl(zpl)?;
+        Ok(self.render(&commands))
+    }
+}
+
+impl LabelRenderer {
+    pub fn for_commands(dpmm: u8, commands: &[ZplCommand]) -> Self {
+        let defaults = Self::default();
+        let (width, length) = label_dimensions(commands);
+        Self::new(
+            dpmm,
+            width.map_or(defaults.width, |dots| dots_to_inches(dots, dpmm)),
+            length.map_or(defaults.height, |dots| dots_to_inches(dots, dpmm)),
+        )
+    }
+}
+
+impl Default for LabelRenderer {
+    fn default() -> Self {
+        Self::new(8, 4.0, 6.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum Rotation {
+    #[default]
+    Normal,
+    Rotated90,
+    Rotated180,
+    Rotated270,
+}
+
+impl From<FontOrientation> for Rotation {
+    fn from(orientation: FontOrientation) -> Self {
+        match orientation {
+            FontOrientation::Normal => Rotation::Normal,
+            FontOrientation::Rotated90 => Rotation::Rotated90,
+            FontOrientation::Rotated180 => Rotation::Rotated180,
+            FontOrientation::Rotated270 => Rotation::Rotated270,
+        }
+    }
+}
+
+impl From<FieldOrientation> for Rotation {
+    fn from(orientation: FieldOrientation) -> Self {
+        match orientation {
+            FieldOrientation::Normal => Rotation::Normal,
+            FieldOrientation::Rotated90 => Rotation::Rotated90,
+            FieldOrientation::Rotated180 => Rotation::Rotated180,
+            FieldOrientation::Rotated270 => Rotation::Rotated270,
+        }
+    }
+}
+
+impl From<FieldRotation> for Rotation {
+    fn from(rotation: FieldRotation) -> Self {
+        match rotation {
+            FieldRotation::Normal => Rotation::Normal,
+            FieldRotation::Rotated90 => Rotation::Rotated90,
+            FieldRotation::Rotated180 => Rotation::Rotated180,
+            FieldRotation::Rotated270 => Rotation::Rotated270,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FontSize {
+    height: u32,
+    width: u32,
+}
+
+impl Default for FontSize {
+    fn default() -> Self {
+        Self {
+            height: 9,
+            width: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct BarcodeDefaults {
+    module_width: u32,
+    ratio: f32,
+    height: u32,
+}
+
+impl Default for BarcodeDefaults {
+    fn default() -> Self {
+        Self {
+            module_width: 2,
+            ratio: 3.0,
+            height: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FieldBlockLayout {
+    width: u32,
+    max_lines: u32,
+    line_spacing: i32,
+    justification: char,
+}
+
+#[derive(Debug, Clone)]
+struct StoredGraphic {
+    width: u32,
+    height: u32,
+    data: String,
+}
+
+#[derive(Default)]
+struct RenderState {
+    origin: (i64, i64),
+    default_font: FontSize,
+    default_rotation: Rotation,
+    field_font: Option<(Rotation, FontSize)>,
+    field_block: Option<FieldBlockLayout>,
+    field_data: Option<String>,
+    field_barcode: Option<ZplCommand>,
+    field_reverse: bool,
+    barcode_defaults: BarcodeDefaults,
+    graphics: HashMap<String, StoredGraphic>,
+    label_home: (i64, i64),
+    label_shift: i64,
+    label_top: i64,
+    print_inverted: bool,
+    serial_step: u64,
+    clock: NaiveDateTime,
+    field_clock: Vec<char>,
+    font_aliases: Vec<char>,
+    typeset: bool,
+}
+
+impl RenderState {
+    fn font_size(&self, font: &FontName, height: u32, width: u32) -> FontSize {
+        let bitmap = match font {
+            FontName::Letter(letter) if !self.font_aliases.contains(letter) => font.zpl_font(),
+            _ => None,
+        };
+        match bitmap.and_then(|font| Some((font, font.bitmap_matrix()?))) {
+            Some((font, (base_height, base_width, _))) => {
+                let style = TextStyle::new(font, height, width);
+                FontSize {
+                    height: style.line_height(),
+                    width: base_height * style.measure("M") / base_width,
+                }
+            }
+            None => FontSize { height, width },
+        }
+    }
+
+    fn apply(&mut self, command: &ZplCommand, canvas: &mut GrayImage) {
+        match command {
+            ZplCommand::StartFormat => {
+                self.reset_field();
+                self.origin = (0, 0);
+                self.typeset = false;
+                self.default_font = FontSize::default();
+                self.default_rotation = Rotation::Normal;
+                self.barcode_defaults = BarcodeDefaults::default();
+            }
+            ZplCommand::EndFormat => self.flush_field(canvas),
+            ZplCommand::PrintWidth { .. } | ZplCommand::LabelLength { .. } => {}
+            ZplCommand::LabelHome { x, y } => self.label_home = (*x as i64, *y as i64),
+            ZplCommand::LabelShift { shift } => self.label_shift = *shift as i64,
+            ZplCommand::LabelTop { offset } => self.label_top = *offset as i64,
+            ZplCommand::PrintOrientation { inverted } => self.print_inverted = *inverted,
+            ZplCommand::FieldOrigin { x, y } | ZplCommand::FieldTypeset { x, y, .. } => {
+                self.origin = (
+                    self.label_home.0 + *x as i64 - self.label_shift,
+                    self.label_home.1 + *y as i64 + self.label_top,
+                );
+                self.typeset = matches!(command, ZplCommand::FieldTypeset { .. });
+            }
+            ZplCommand::Font {
+                font,
+                orientation,
+                height,
+                width,
+            } => {
+                self.field_font = Some((
+                    Rotation::from(*orientation),
+                    self.font_size(font, *height, *width),
+                ));
+            }
+            ZplCommand::FontAlias { alias, .. } => {
+                if !self.font_aliases.contains(alias) {
+                    self.font_aliases.push(*alias);
+                }
+            }
+            ZplCommand::ChangeFont { size, .. } => {
+                if *size > 0 {
+                    self.default_font = FontSize {
+                        height: *size,
+                        width: *size,
+                    };
+                }
+            }
+            ZplCommand::FieldOrientation { rotation } => {
+                self.default_rotation = Rotation::from(*rotation);
+            }
+            ZplCommand::FieldData { data } => self.field_data = Some(data.clone()),
+            ZplCommand::SerialNumber {
+                start,
+                increment,
+                leading_zeros,
+            } => {
+                self.field_data = Some(serial_number_value(
+                    start,
+                    *increment,
+                    *leading_zeros,
+                    self.serial_step,
+                ));
+            }
+            ZplCommand::FieldClock {
+                primary,
+                secondary,
+                tertiary,
+            } => {
+                self.field_clock = std::iter::once(*primary)
+                    .chain(*secondary)
+                    .chain(*tertiary)
+                    .collect();
+            }
+            ZplCommand::SerializationField { mask, increment } => {
+                if let Some(data) = &self.field_data {
+                    self.field_data =
+                        Some(serialize_field(data, mask, increment, self.serial_step));
+                }
+            }
+            ZplCommand::FieldSeparator => self.flush_field(canvas),
+            ZplCommand::FieldBlock {
+                width,
+                max_lines,
+                line_spacing,
+                justification,
+            } => {
+                self.field_block = Some(FieldBlockLayout {
+                    width: *width,
+                    max_lines: *max_lines,
+                    line_spacing: *line_spacing,
+                    justification: *justification,
+                });
+            }
+            ZplCommand::GraphicBox {
+                width,
+                height,
+                thickness,
+                color,
+                rounding,
+            } => {
+                draw_box(
+                    canvas,
+                    self.origin,
+                    *width,
+                    *height,
+                    *thickness,
+                    rounding.unwrap_or(0),
+                    self.line_paint(*color),
+                );
+            }
+            ZplCommand::GraphicCircle {
+                diameter,
+                thickness,
+                color,
+            } => {
+                draw_ellipse(
+                    canvas,
+                    self.origin,
+                    *diameter,
+                    *diameter,
+                    *thickness,
+                    self.line_paint(*color),
+                );
+            }
+            ZplCommand::GraphicEllipse {
+                width,
+                height,
+                thickness,
+                color,
+            } => {
+                draw_ellipse(
+                    canvas,
+                    self.origin,
+                    *width,
+                    *height,
+                    *thickness,
+                    self.line_paint(*color),
+                );
+            }
+            ZplCommand::GraphicDiagonalLine {
+                width,
+                height,
+                thickness,
+                color,
+                orientation,
+            } => {
+                draw_diagonal_line(
+                    canvas,
+                    self.origin,
+                    *width,
+                    *height,
+                    *thickness,
+                    *orientation == 'L',
+                    self.line_paint(*color),
+                );
+            }
+            ZplCommand::FieldReverse => self.field_reverse = true,
+            ZplCommand::GraphicField {
+                width,
+                height,
+                data,
+                ..
+            } => {
+                let paint = self.paint(BLACK);
+                draw_hex_graphic(canvas, self.origin, *width, *height, data, (1, 1), paint);
+            }
+            ZplCommand::DownloadGraphic {
+                name,
+                width,
+                height,
+                data,
+                ..
+            } => {
+                self.graphics.insert(
+                    name.to_uppercase(),
+                    StoredGraphic {
+                        width: *width,
+                        height: *height,
+                        data: data.clone(),
+                    },
+                );
+            }
+            ZplCommand::RecallGraphic {
+                name,
+                magnification_x,
+                magnification_y,
+            } => {
+                let paint = self.paint(BLACK);
+                if let Some(graphic) = self.graphics.get(&name.to_uppercase()) {
+                    draw_hex_graphic(
+                        canvas,
+                        self.origin,
+                        graphic.width,
+                        graphic.height,
+                        &graphic.data,
+                        (*magnification_x, *magnification_y),
+                        paint,
+                    );
+                }
+            }
+            ZplCommand::BarcodeFieldDefault {
+                width,
+                ratio,
+                height,
+            } => {
+                self.barcode_defaults = BarcodeDefaults {
+                    module_width: (*width).max(1),
+                    ratio: *ratio,
+                    height: *height,
+                };
+            }
+            ZplCommand::Code128Barcode { .. }
+            | ZplCommand::Code39Barcode { .. }
+            | ZplCommand::Ean13Barcode { .. }
+            | ZplCommand::UpcABarcode { .. }
+            | ZplCommand::Interleaved2of5Barcode { .. }
+            | ZplCommand::Ean8Barcode { .. }
+            | ZplCommand::QrCode { .. }
+            | ZplCommand::DataMatrix { .. }
+            | ZplCommand::Pdf417 { .. } => self.field_barcode = Some(command.clone()),
+            ZplCommand::ChangeEncoding { .. }
+            | ZplCommand::ClockMode { .. }
+            | ZplCommand::SetTime { .. }
+            | ZplCommand::PrintQuantity { .. }
+            | ZplCommand::DownloadFormat { .. }
+            | ZplCommand::RecallFormat { .. }
+            | ZplCommand::FieldNumber { .. }
+            | ZplCommand::PrintRate { .. }
+            | ZplCommand::MediaDarkness { .. }
+            | ZplCommand::SetDarkness { .. }
+            | ZplCommand::MediaType { .. }
+            | ZplCommand::MediaTracking { .. }
+            | ZplCommand::MediaMode { .. }
+            | ZplCommand::ImmediateCut { .. }
+            | ZplCommand::DelayedCut
+            | ZplCommand::KioskValues { .. }
+            | ZplCommand::DownloadFont { .. }
+            | ZplCommand::Comment { .. }
+            | ZplCommand::Raw { .. } => {}
+        }
+    }
+
+    fn field_position(&self, mask: &GrayImage) -> (i64, i64) {
+        if self.typeset {
+            (self.origin.0, self.origin.1 - mask.height() as i64)
+        } else {
+            self.origin
+        }
+    }
+
+    fn reset_field(&mut self) {
+        self.field_font = None;
+        self.field_block = None;
+        self.field_data = None;
+        self.field_barcode = None;
+        self.field_reverse = false;
+        self.field_clock.clear();
+    }
+
+    fn paint(&self, color: Luma<u8>) -> Paint {
+        if self.field_reverse {
+            Paint::Reverse
+        } else {
+            Paint::Solid(color)
+        }
+    }
+
+    fn line_paint(&self, color: Option<char>) -> Paint {
+        self.paint(if color == Some('W') { WHITE } else { BLACK })
+    }
+
+    fn flush_field(&mut self, canvas: &mut GrayImage) {
+        let Some(mut data) = self.field_data.take() else {
+            self.reset_field();
+            return;
+        };
+        if !self.field_clock.is_empty() {
+            data = expand_clock_data(&data, &self.field_clock, self.clock);
+        }
+        let paint = self.paint(BLACK);
+
+        if let Some(barcode) = self.field_barcode.clone() {
+            if let Some((mask, rotation)) = self.barcode_mask(&barcode, &data) {
+                let mask = rotate_mask(&mask, rotation);
+                draw_mask(canvas, &mask, self.field_position(&mask), paint);
+            }
+        } else {
+            let (rotation, size) = self
+                .field_font
+                .unwrap_or((self.default_rotation, self.default_font));
+            let mask = match &self.field_block {
+                Some(block) => field_block_mask(&data, size, block),
+                None => text_mask(&data, size),
+            };
+            let mask = rotate_mask(&mask, rotation);
+            draw_mask(canvas, &mask, self.field_position(&mask), paint);
+        }
+        self.reset_field();
+    }
+
+    fn barcode_mask(&self, barcode: &ZplCommand, data: &str) -> Option<(GrayImage, Rotation)> {
+        let defaults = self.barcode_defaults;
+        let module_width = defaults.module_width;
+        let (symbol, orientation, height, interpretation) = match barcode {
+            ZplCommand::Code128Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+                mode,
+            } => (
+                code128_symbol(data, *mode, *check_digit, module_width).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::Code39Barcode {
+                orientation,
+                check_digit,
+                height,
+                print_interpretation,
+                print_above,
+            } => (
+                code39_symbol(data, *check_digit, module_width, defaults.ratio).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::Ean13Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => (
+                ean13_symbol(data, module_width).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::UpcABarcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                print_check_digit,
+            } => (
+                upc_a_symbol(data, *print_check_digit, module_width).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::Interleaved2of5Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+            } => (
+                interleaved_2of5_symbol(data, *check_digit, module_width, defaults.ratio).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::Ean8Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => (
+                ean8_symbol(data, module_width).ok()?,
+                orientation,
+                height,
+                print_interpretation.then_some(*print_above),
+            ),
+            ZplCommand::QrCode { magnification, .. } => {
+                let field = data.parse::<QrFieldData>().ok()?;
+                let symbol = qr_code_symbol(&field).ok()?;
+                return Some((
+                    matrix_barcode_mask(&symbol, *magnification, *magnification),
+                    Rotation::Normal,
+                ));
+            }
+            ZplCommand::DataMatrix {
+                orientation,
+                height,
+                columns,
+                rows,
+                ..
+            } => {
+                let (columns, rows) = data_matrix_size(data, *columns, *rows).ok()?;
+                let module = (*height).max(1);
+                return Some((
+                    placeholder_mask(columns * module, rows * module),
+                    Rotation::from(*orientation),
+                ));
+            }
+            ZplCommand::Pdf417 {
+                orientation,
+                row_height,
+                security_level,
+                columns,
+                rows,
+                ..
+            } => {
+                let (columns, rows) = pdf417_size(data, *security_level, *columns, *rows).ok()?;
+                return Some((
+                    placeholder_mask(
+                        (17 * columns + 69) * module_width,
+                        rows * (*row_height).max(1),
+                    ),
+                    Rotation::from(*orientation),
+                ));
+            }
+            _ => return None,
+        };
+
+        let height = if *height > 0 {
+            *height
+        } else {
+            defaults.height
+        };
+        Some((
+            linear_barcode_mask(&symbol, height, interpretation, module_width),
+            Rotation::from(*orientation),
+        ))
+    }
+}
+
+fn placeholder_mask(width: u32, height: u32) -> GrayImage {
+    let (width, height) = (width.max(1), height.max(1));
+    let border = (width.min(height) / 20).max(1);
+    GrayImage::from_fn(width, height, |x, y| {
+        let edge = x < border || y < border || x >= width - border || y >= height - border;
+        let diagonal = x as u64 * height as u64 / width as u64;
+        let cross = (y as u64).abs_diff(diagonal) < border as u64
+            || (height as u64 - 1 - y as u64).abs_diff(diagonal) < border as u64;
+        if edge || cross { INK } else { Luma([0]) }
+    })
+}
+
+fn matrix_barcode_mask(symbol: &MatrixSymbol, module_width: u32, module_height: u32) -> GrayImage {
+    let module_width = module_width.max(1);
+    let module_height = module_height.max(1);
+    let mut mask = GrayImage::new(
+        (symbol.columns * module_width).max(1),
+        (symbol.rows * module_height).max(1),
+    );
+    for row in 0..symbol.rows {
+        for column in 0..symbol.columns {
+            if !symbol.is_dark(column, row) {
+                continue;
+            }
+            for y in row * module_height..(row + 1) * module_height {
+                for x in column * module_width..(column + 1) * module_width {
+                    mask.put_pixel(x, y, INK);
+                }
+            }
+        }
+    }
+    mask
+}
+
+fn linear_barcode_mask(
+    symbol: &LinearSymbol,
+    bar_height: u32,
+    interpretation: Option<bool>,
+    module_width: u32,
+) -> GrayImage {
+    let text_size = FontSize {
+        height: 9 * module_width,
+        width: 5 * module_width,
+    };
+    let text = interpretation.map(|_| text_mask(&symbol.text, text_size));
+    let gap = module_width;
+    let text_height = text.as_ref().map(|mask| mask.height() + gap).unwrap_or(0);
+    let width = symbol
+        .width()
+        .max(text.as_ref().map(|mask| mask.width()).unwrap_or(0))
+        .max(1);
+    let mut mask = GrayImage::new(width, bar_height.max(1) + text_height);
+
+    let bars_y = if interpretation == Some(true) {
+        text_height as i64
+    } else {
+        0
+    };
+    let mut caret = 0;
+    for (index, element) in symbol.elements.iter().enumerate() {
+        if index % 2 == 0 {
+            for x in caret..caret + element {
+                for y in 0..bar_height {
+                    set_pixel(&mut mask, x as i64, bars_y + y as i64, Paint::Solid(INK));
+                }
+            }
+        }
+        caret += element;
+    }
+
+    if let Some(text) = text {
+        let text_x = (symbol.width() as i64 - text.width() as i64).max(0) / 2;
+        let text_y = if interpretation == Some(true) {
+            0
+        } else {
+            (bar_height + gap) as i64
+        };
+        draw_mask(&mut mask, &text, (text_x, text_y), Paint::Solid(INK));
+    }
+
+    mask
+}
+
+fn font_scale(size: FontSize) -> PxScale {
+    scalable_font_scale(size.height, size.width)
+}
+
+fn text_width(text: &str, size: FontSize) -> u32 {
+    scalable_text_width(text, size.height, size.width)
+}
+
+fn text_mask(text: &str, size: FontSize) -> GrayImage {
+    let font = scalable_font();
+    let scale = font_scale(size);
+    let scaled = font.as_scaled(scale);
+    let height = size.height.max(1);
+    let embolden = (height / 20).max(1);
+    let mut mask = GrayImage::new(text_width(text, size) + embolden, height);
+
+    let mut caret = 0.0;
+    let mut previous = None;
+    for character in text.chars() {
+        let glyph_id = font.glyph_id(character);
+        if let Some(previous_id) = previous {
+            caret += scaled.kern(previous_id, glyph_id);
+        }
+        let glyph = glyph_id.with_scale_and_position(scale, point(caret, scaled.ascent()));
+        caret += scaled.h_advance(glyph_id);
+        previous = Some(glyph_id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|x, y, coverage| {
+                if coverage < 0.3 {
+                    return;
+                }
+                let pixel_y = bounds.min.y as i64 + y as i64;
+                for offset in 0..=embolden {
+                    let pixel_x = bounds.min.x as i64 + x as i64 + offset as i64;
+                    if pixel_x >= 0
+                        && pixel_y >= 0
+                        && (pixel_x as u32) < mask.width()
+                        && (pixel_y as u32) < mask.height()
+                    {
+                        mask.put_pixel(pixel_x as u32, pixel_y as u32, INK);
+                    }
+                }
+            });
+        }
+    }
+
+    mask
+}
+
+fn wrap_text(text: &str, size: FontSize, max_width: u32) -> Vec<String> {
+    wrap_lines(
+        text,
+        TextStyle::scalable(size.height, size.width),
+        max_width,
+    )
+}
+
+fn field_block_mask(text: &str, size: FontSize, block: &FieldBlockLayout) -> GrayImage {
+    let max_lines = block.max_lines.max(1) as usize;
+    let mut lines = wrap_text(text, size, block.width);
+    if lines.len() > max_lines {
+        let overflow = lines.split_off(max_lines - 1).join(" ");
+        lines.push(overflow);
+    }
+
+    let line_height = (size.height as i64 + block.line_spacing as i64).max(1);
+    let block_height = (line_height * (lines.len() as i64 - 1) + size.height as i64).max(1);
+    let mut mask = GrayImage::new(block.width.max(1), block_height as u32);
+
+    for (index, line) in lines.iter().enumerate() {
+        let line_y = line_height * index as i64;
+        let is_last_line = index + 1 == lines.len();
+        let line_mask = if block.justification == 'J' && !is_last_line {
+            justified_line_mask(line, size, block.width)
+        } else {
+            text_mask(line, size)
+        };
+        let line_x = match block.justification {
+            'C' => (block.width as i64 - line_mask.width() as i64) / 2,
+            'R' => block.width as i64 - line_mask.width() as i64,
+            _ => 0,
+        };
+        draw_mask(
+            &mut mask,
+            &line_mask,
+            (line_x.max(0), line_y),
+            Paint::Solid(INK),
+        );
+    }
+
+    mask
+}
+
+fn justified_line_mask(line: &str, size: FontSize, width: u32) -> GrayImage {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() < 2 {
+        return text_mask(line, size);
+    }
+
+    let word_masks: Vec<GrayImage> = words.iter().map(|word| text_mask(word, size)).collect();
+    let words_width: u32 = word_masks.iter().map(|mask| mask.width()).sum();
+    let gap = width.saturating_sub(words_width) as f32 / (words.len() - 1) as f32;
+
+    let mut mask = GrayImage::new(width.max(1), size.height.max(1));
+    let mut caret = 0.0;
+    for word_mask in &word_masks {
+        draw_mask(&mut mask, word_mask, (caret as i64, 0), Paint::Solid(INK));
+        caret += word_mask.width() as f32 + gap;
+    }
+    mask
+}
+
+fn rotate_mask(mask: &GrayImage, rotation: Rotation) -> GrayImage {
+    match rotation {
+        Rotation::Normal => mask.clone(),
+        Rotation::Rotated90 => imageops::rotate90(mask),
+        Rotation::Rotated180 => imageops::rotate180(mask),
+        Rotation::Rotated270 => imageops::rotate270(mask),
+    }
+}
+
+fn draw_mask(canvas: &mut GrayImage, mask: &GrayImage, origin: (i64, i64), paint: Paint) {
+    for (x, y, pixel) in mask.enumerate_pixels() {
+        if pixel[0] > 0 {
+            set_pixel(canvas, origin.0 + x as i64, origin.1 + y as i64, paint);
+        }
+    }
+}
+
+fn set_pixel(canvas: &mut GrayImage, x: i64, y: i64, paint: Paint) {
+    if x >= 0 && y >= 0 && (x as u32) < canvas.width() && (y as u32) < canvas.height() {
+        let color = match paint {
+            Paint::Solid(color) => color,
+            Paint::Reverse => Luma([255 - canvas.get_pixel(x as u32, y as u32)[0]]),
+        };
+        canvas.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+fn inside_rounded_rect(x: f32, y: f32, width: f32, height: f32, radius: f32) -> bool {
+    if x < 0.0 || y < 0.0 || x > width || y > height {
+        return false;
+    }
+    let dx = (radius - x).max(x - (width - radius)).max(0.0);
+    let dy = (radius - y).max(y - (height - radius)).max(0.0);
+    dx * dx + dy * dy <= radius * radius
+}
+
+fn draw_box(
+    canvas: &mut GrayImage,
+    origin: (i64, i64),
+    width: u32,
+    height: u32,
+    thickness: u32,
+    rounding: u8,
+    paint: Paint,
+) {
+    let thickness = thickness.max(1);
+    let width = width.max(thickness);
+    let height = height.max(thickness);
+    let radius = rounding.min(8) as f32 / 8.0 * (width.min(height) as f32 / 2.0);
+    let inner_width = width as f32 - 2.0 * thickness as f32;
+    let inner_height = height as f32 - 2.0 * thickness as f32;
+    let inner_radius = (radius - thickness as f32).max(0.0);
+
+    for y in 0..height {
+        for x in 0..width {
+            let center_x = x as f32 + 0.5;
+            let center_y = y as f32 + 0.5;
+            if !inside_rounded_rect(center_x, center_y, width as f32, height as f32, radius) {
+                continue;
+            }
+            let in_hole = inner_width > 0.0
+                && inner_height > 0.0
+                && inside_rounded_rect(
+                    center_x - thickness as f32,
+                    center_y - thickness as f32,
+                    inner_width,
+                    inner_height,
+                    inner_radius,
+                );
+            if !in_hole {
+                set_pixel(canvas, origin.0 + x as i64, origin.1 + y as i64, paint);
+            }
+        }
+    }
+}
+
+fn inside_ellipse(x: f32, y: f32, width: f32, height: f32) -> bool {
+    let radius_x = width / 2.0;
+    let radius_y = height / 2.0;
+    let dx = (x - radius_x) / radius_x;
+    let dy = (y - radius_y) / radius_y;
+    dx * dx + dy * dy <= 1.0
+}
+
+fn draw_ellipse(
+    canvas: &mut GrayImage,
+    origin: (i64, i64),
+    width: u32,
+    height: u32,
+    thickness: u32,
+    paint: Paint,
+) {
+    let thickness = thickness.max(1);
+    let width = width.max(1);
+    let height = height.max(1);
+    let inner_width = width as f32 - 2.0 * thickness as f32;
+    let inner_height = height as f32 - 2.0 * thickness as f32;
+
+    for y in 0..height {
+        for x in 0..width {
+            let center_x = x as f32 + 0.5;
+            let center_y = y as f32 + 0.5;
+            if !inside_ellipse(center_x, center_y, width as f32, height as f32) {
+                continue;
+            }
+            let in_hole = inner_width > 0.0
+                && inner_height > 0.0
+                && inside_ellipse(
+                    center_x - thickness as f32,
+                    center_y - thickness as f32,
+                    inner_width,
+                    inner_height,
+                );
+            if !in_hole {
+                set_pixel(canvas, origin.0 + x as i64, origin.1 + y as i64, paint);
+            }
+        }
+    }
+}
+
+fn draw_diagonal_line(
+    canvas: &mut GrayImage,
+    origin: (i64, i64),
+    width: u32,
+    height: u32,
+    thickness: u32,
+    left_leaning: bool,
+    paint: Paint,
+) {
+    let thickness = thickness.max(1);
+    let width = width.max(thickness);
+    let height = height.max(1);
+    let travel = (width - thickness) as f32;
+
+    for y in 0..height {
+        let progress = if height > 1 {
+            y as f32 / (height - 1) as f32
+        } else {
+            0.0
+        };
+        let start = if left_leaning {
+            travel * progress
+        } else {
+            travel * (1.0 - progress)
+        }
+        .round() as u32;
+        for x in start..start + thickness {
+            set_pixel(canvas, origin.0 + x as i64, origin.1 + y as i64, paint);
+        }
+    }
+}
+
+fn draw_hex_graphic(
+    canvas: &mut GrayImage,
+    origin: (i64, i64),
+    width: u32,
+    height: u32,
+    data: &str,
+    magnification: (u32, u32),
+    paint: Paint,
+) {
+    let bytes_per_row = width.div_ceil(8) as usize;
+    let hex: Vec<u8> = data.bytes().filter(u8::is_ascii_hexdigit).collect();
+    let bytes: Vec<u8> = hex
+        .chunks(2)
+        .filter_map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect();
+    let magnification_x = magnification.0.max(1) as i64;
+    let magnification_y = magnification.1.max(1) as i64;
+
+    for row in 0..height as usize {
+        for column in 0..width as usize {
+            let Some(byte) = bytes.get(row * bytes_per_row + column / 8) else {
+                return;
+            };
+            if byte & (0x80 >> (column % 8)) == 0 {
+                continue;
+            }
+            for dy in 0..magnification_y {
+                for dx in 0..magnification_x {
+                    set_pixel(
+                        canvas,
+                        origin.0 + column as i64 * magnification_x + dx,
+                        origin.1 + row as i64 * magnification_y + dy,
+                        paint,
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_graphic_ignores_line_breaks() {
+        let mut mask = GrayImage::new(16, 2);
+        draw_hex_graphic(
+            &mut mask,
+            (0, 0),
+            16,
+            2,
+            "FF00\n00FF",
+            (1, 1),
+            Paint::Solid(INK),
+        );
+        let inked = |x, y| mask.get_pixel(x, y) == &INK;
+        assert!((0..16).all(|x| inked(x, 0) == (x < 8)));
+        assert!((0..16).all(|x| inked(x, 1) == (x >= 8)));
+    }
+    #[test]
+    fn data_matrix_preview_draws_a_placeholder() {
+        let renderer = LabelRenderer::new(8, 2.0, 1.0);
+        let canvas = renderer
+            .render_zpl("^XA^FO10,10^BXN,5,200^FD123456^FS^XZ")
+            .unwrap();
+        assert_eq!(canvas.get_pixel(10, 10), &BLACK);
+        assert_eq!(canvas.get_pixel(59, 59), &BLACK);
+        assert_eq!(canvas.get_pixel(60, 60), &WHITE);
+    }
+}
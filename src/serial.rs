@@ -0,0 +1,115 @@
+use crate::zpl::ZplCommand;
+
+pub fn serial_number_value(start: &str, increment: i64, leading_zeros: bool, step: u64) -> String {
+    if step == 0 {
+        return start.to_string();
+    }
+    let Some(end) = start.rfind(|c: char| c.is_ascii_digit()) else {
+        return start.to_string();
+    };
+    let begin = start[..end]
+        .rfind(|c: char| !c.is_ascii_digit())
+        .map_or(0, |index| index + 1);
+
+    let digits = &start[begin..=end];
+    let width = digits.len().min(18) as u32;
+    let modulus = 10i128.pow(width);
+    let value = digits.parse::<i128>().unwrap_or(0) + increment as i128 * step as i128;
+    let value = value.rem_euclid(modulus);
+
+    let number = if leading_zeros {
+        format!("{:0width$}", value, width = digits.len())
+    } else {
+        value.to_string()
+    };
+    format!("{}{}{}", &start[..begin], number, &start[end + 1..])
+}
+
+pub fn serialize_field(data: &str, mask: &str, increment: &str, step: u64) -> String {
+    let mut characters: Vec<char> = data.chars().collect();
+    let mask: Vec<char> = mask.chars().collect();
+    let increment: Vec<char> = increment.chars().collect();
+
+    let mut carry: u128 = 0;
+    for offset in 0..mask.len().min(characters.len()) {
+        let mask_char = mask[mask.len() - 1 - offset];
+        let Some(alphabet) = mask_alphabet(mask_char) else {
+            continue;
+        };
+        let position = characters.len() - 1 - offset;
+        let Some(current) = alphabet.find(characters[position]) else {
+            continue;
+        };
+        let amount = increment
+            .len()
+            .checked_sub(offset + 1)
+            .and_then(|index| digit_value(increment[index], alphabet))
+            .unwrap_or(0);
+
+        let base = alphabet.len() as u128;
+        let sum = current as u128 + amount as u128 * step as u128 + carry;
+        characters[position] = alphabet.as_bytes()[(sum % base) as usize] as char;
+        carry = sum / base;
+    }
+
+    characters.into_iter().collect()
+}
+
+fn mask_alphabet(mask: char) -> Option<&'static str> {
+    match mask {
+        'D' | 'd' => Some("0123456789"),
+        'H' => Some("0123456789ABCDEF"),
+        'h' => Some("0123456789abcdef"),
+        'O' | 'o' => Some("01234567"),
+        'A' => Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+        'a' => Some("abcdefghijklmnopqrstuvwxyz"),
+        'N' => Some("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ"),
+        'n' => Some("0123456789abcdefghijklmnopqrstuvwxyz"),
+        _ => None,
+    }
+}
+
+fn digit_value(character: char, alphabet: &str) -> Option<usize> {
+    alphabet
+        .find(character)
+        .or_else(|| character.to_digit(10).map(|digit| digit as usize))
+}
+
+pub fn serial_step(commands: &[ZplCommand], label_index: u64) -> u64 {
+    let replicates = commands
+        .iter()
+        .rev()
+        .find_map(|command| match command {
+            ZplCommand::PrintQuantity { replicates, .. } => Some(*replicates as u64),
+            _ => None,
+        })
+        .unwrap_or(0);
+    label_index / (replicates + 1)
+}
+
+pub fn with_print_quantity(commands: &[ZplCommand], quantity: u32) -> Vec<ZplCommand> {
+    let mut commands = commands.to_vec();
+    if let Some(ZplCommand::PrintQuantity { quantity: q, .. }) = commands
+        .iter_mut()
+        .rev()
+        .find(|command| matches!(command, ZplCommand::PrintQuantity { .. }))
+    {
+        *q = quantity;
+        return commands;
+    }
+
+    let command = ZplCommand::PrintQuantity {
+        quantity,
+        pause_count: 0,
+        replicates: 0,
+        override_pause: false,
+    };
+    match commands
+        .iter()
+        .rposition(|command| *command == ZplCommand::EndFormat)
+    {
+        Some(index) => commands.insert(index, command),
+        None => commands.push(command),
+    }
+    commands
+}
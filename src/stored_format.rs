@@ -0,0 +1,150 @@
+use crate::template::{render_template, template_fields};
+use crate::zpl::{ZplCommand, commands_to_zpl};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredFormat {
+    pub name: String,
+    pub format: Vec<ZplCommand>,
+    pub fields: Vec<String>,
+}
+
+impl StoredFormat {
+    pub fn from_template(name: impl Into<String>, template: &[ZplCommand]) -> Result<Self, String> {
+        let variable_fields = template_fields(template)?;
+        let mut fields: Vec<String> = Vec::new();
+        let mut format = Vec::new();
+
+        for (index, command) in template.iter().enumerate() {
+            match command {
+                ZplCommand::StartFormat | ZplCommand::EndFormat => {}
+                ZplCommand::FieldData { data } if variable_fields.contains(&index) => {
+                    let number = match fields.iter().position(|field| field == data) {
+                        Some(position) => position,
+                        None => {
+                            fields.push(data.clone());
+                            fields.len() - 1
+                        }
+                    };
+                    format.push(ZplCommand::FieldNumber {
+                        number: number as u32 + 1,
+                    });
+                }
+                _ => format.push(command.clone()),
+            }
+        }
+
+        Ok(Self {
+            name: name.into(),
+            format,
+            fields,
+        })
+    }
+
+    pub fn field_number(&self, field: &str) -> Option<u32> {
+        self.fields
+            .iter()
+            .position(|template| template == field)
+            .map(|index| index as u32 + 1)
+    }
+
+    pub fn download_commands(&self) -> Vec<ZplCommand> {
+        let mut commands = vec![
+            ZplCommand::StartFormat,
+            ZplCommand::DownloadFormat {
+                name: self.name.clone(),
+            },
+            ZplCommand::FieldSeparator,
+        ];
+        commands.extend(self.format.iter().cloned());
+        commands.push(ZplCommand::EndFormat);
+        commands
+    }
+
+    pub fn job_commands(
+        &self,
+        values: &HashMap<String, String>,
+    ) -> Result<Vec<ZplCommand>, String> {
+        let mut commands = vec![
+            ZplCommand::StartFormat,
+            ZplCommand::RecallFormat {
+                name: self.name.clone(),
+            },
+            ZplCommand::FieldSeparator,
+        ];
+        for (index, field) in self.fields.iter().enumerate() {
+            let data = render_template(field, values)
+                .map_err(|e| format!("Field ^FN{}: {}", index + 1, e))?;
+            commands.push(ZplCommand::FieldNumber {
+                number: index as u32 + 1,
+            });
+            commands.push(ZplCommand::FieldData { data });
+            commands.push(ZplCommand::FieldSeparator);
+        }
+        commands.push(ZplCommand::EndFormat);
+        Ok(commands)
+    }
+
+    pub fn download_zpl(&self) -> String {
+        commands_to_zpl(&self.download_commands())
+    }
+
+    pub fn job_zpl(&self, values: &HashMap<String, String>) -> Result<String, String> {
+        Ok(commands_to_zpl(&self.job_commands(values)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_zpl;
+    use crate::template::bind_commands;
+
+    fn order_format() -> StoredFormat {
+        let template =
+            parse_zpl("^XA^FO20,20^FDLot: {{lot}}^FS^FO20,80^FD{{name | upper}}^FS^XZ").unwrap();
+        StoredFormat::from_template("R:ORDER.ZPL", &template).unwrap()
+    }
+
+    #[test]
+    fn mixed_fields_become_field_numbers() {
+        let download = order_format().download_zpl();
+        assert!(!download.contains("{{"), "{}", download);
+        assert!(download.contains("^FN1"));
+        assert!(download.contains("^FN2"));
+    }
+
+    #[test]
+    fn job_renders_whole_field_text() {
+        let values = HashMap::from([
+            ("lot".to_string(), "A7".to_string()),
+            ("name".to_string(), "widget".to_string()),
+        ]);
+        let job = order_format().job_zpl(&values).unwrap();
+        assert!(job.contains("^FN1\n^FDLot: A7\n^FS"), "{}", job);
+        assert!(job.contains("^FN2\n^FDWIDGET\n^FS"), "{}", job);
+    }
+
+    #[test]
+    fn jobs_print_the_same_text_as_bound_commands() {
+        let template =
+            parse_zpl("^XA^FO20,20^FD#{{id | pad:4}} {{name | default:N/A}}^FS^XZ").unwrap();
+        let record = HashMap::from([("id".to_string(), "7".to_string())]);
+        let format = StoredFormat::from_template("R:ORDER.ZPL", &template).unwrap();
+        let job = format.job_commands(&record).unwrap();
+        let bound = bind_commands(&template, &record).unwrap();
+        assert_eq!(job[4], bound[2]);
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        let template = parse_zpl("^XA^FO20,20^FD{{name | bogus}}^FS^XZ").unwrap();
+        assert!(StoredFormat::from_template("R:ORDER.ZPL", &template).is_err());
+    }
+
+    #[test]
+    fn missing_value_is_an_error() {
+        let values = HashMap::from([("lot".to_string(), "A7".to_string())]);
+        assert!(order_format().job_zpl(&values).is_err());
+    }
+}
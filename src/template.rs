@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use serde_json::Value;
+
+use crate::zpl::ZplCommand;
+
+pub type Record = HashMap<String, String>;
+
+const DATE_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%m/%d/%Y %H:%M:%S",
+    "%m/%d/%Y %H:%M",
+];
+
+const DATE_ONLY_FORMATS: [&str; 2] = ["%Y-%m-%d", "%m/%d/%Y"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Upper,
+    Lower,
+    Trim,
+    Date(String),
+    Pad { width: usize, fill: char },
+    Default(String),
+}
+
+impl Filter {
+    pub fn apply(&self, value: &str) -> Result<String, String> {
+        match self {
+            Filter::Upper => Ok(value.to_uppercase()),
+            Filter::Lower => Ok(value.to_lowercase()),
+            Filter::Trim => Ok(value.trim().to_string()),
+            Filter::Date(format) => Ok(parse_date(value)?.format(format).to_string()),
+            Filter::Pad { width, fill } => {
+                let padding = width.saturating_sub(value.chars().count());
+                Ok(std::iter::repeat_n(*fill, padding)
+                    .chain(value.chars())
+                    .collect())
+            }
+            Filter::Default(_) => Ok(value.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Filter::Upper => write!(f, "upper"),
+            Filter::Lower => write!(f, "lower"),
+            Filter::Trim => write!(f, "trim"),
+            Filter::Date(format) => write!(f, "date:{}", format),
+            Filter::Pad { width, fill: '0' } => write!(f, "pad:{}", width),
+            Filter::Pad { width, fill } => write!(f, "pad:{}:{}", width, fill),
+            Filter::Default(default) => write!(f, "default:{}", default),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, argument) = match value.trim().split_once(':') {
+            Some((name, argument)) => (name.trim(), Some(argument.trim())),
+            None => (value.trim(), None),
+        };
+        match (name.to_lowercase().as_str(), argument) {
+            ("upper", None) => Ok(Filter::Upper),
+            ("lower", None) => Ok(Filter::Lower),
+            ("trim", None) => Ok(Filter::Trim),
+            ("date", Some(format)) if !format.is_empty() => {
+                if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                    return Err(format!("Invalid date format: {}", format));
+                }
+                Ok(Filter::Date(format.to_string()))
+            }
+            ("pad", Some(argument)) => {
+                let (width, fill) = match argument.split_once(':') {
+                    Some((width, fill)) => (width, fill.chars().next().unwrap_or('0')),
+                    None => (argument, '0'),
+                };
+                let width = width
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Invalid pad width: {}", width))?;
+                Ok(Filter::Pad { width, fill })
+            }
+            ("default", Some(default)) => Ok(Filter::Default(default.to_string())),
+            _ => Err(format!("Invalid filter: {}", value.trim())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    pub name: String,
+    pub filters: Vec<Filter>,
+}
+
+impl Placeholder {
+    pub fn render(&self, record: &Record) -> Result<String, String> {
+        let mut value = record.get(&self.name).cloned();
+        for filter in &self.filters {
+            value = match (filter, value) {
+                (Filter::Default(default), None) => Some(default.clone()),
+                (Filter::Default(default), Some(value)) if value.is_empty() => {
+                    Some(default.clone())
+                }
+                (_, None) => None,
+                (filter, Some(value)) => Some(
+                    filter
+                        .apply(&value)
+                        .map_err(|e| format!("{{{{{}}}}}: {}", self, e))?,
+                ),
+            };
+        }
+        value.ok_or_else(|| format!("Missing value for {}", self.name))
+    }
+}
+
+impl fmt::Display for Placeholder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for filter in &self.filters {
+            write!(f, " | {}", filter)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Placeholder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut parts = value.split('|');
+        let name = parts.next().unwrap_or("").trim();
+        if name.is_empty() {
+            return Err(format!("Invalid placeholder: {{{{{}}}}}", value));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            filters: parts.map(str::parse).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+pub fn parse_template(text: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            segments.push(Segment::Text(rest[..start].to_string()));
+        }
+        let expression = &rest[start + 2..];
+        let end = expression
+            .find("}}")
+            .ok_or_else(|| format!("Unclosed placeholder in: {}", text))?;
+        segments.push(Segment::Placeholder(expression[..end].parse()?));
+        rest = &expression[end + 2..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest.to_string()));
+    }
+    Ok(segments)
+}
+
+pub fn has_placeholders(text: &str) -> bool {
+    text.contains("{{")
+}
+
+pub fn render_template(text: &str, record: &Record) -> Result<String, String> {
+    parse_template(text)?
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => Ok(text),
+            Segment::Placeholder(placeholder) => placeholder.render(record),
+        })
+        .collect()
+}
+
+pub fn template_fields(commands: &[ZplCommand]) -> Result<Vec<usize>, String> {
+    let mut fields = Vec::new();
+    for (index, command) in commands.iter().enumerate() {
+        if let ZplCommand::FieldData { data } = command
+            && has_placeholders(data)
+        {
+            parse_template(data).map_err(|e| format!("Command #{}: {}", index + 1, e))?;
+            fields.push(index);
+        }
+    }
+    Ok(fields)
+}
+
+pub fn template_variables(commands: &[ZplCommand]) -> Result<Vec<String>, String> {
+    let mut variables: Vec<String> = Vec::new();
+    for index in template_fields(commands)? {
+        if let ZplCommand::FieldData { data } = &commands[index] {
+            for segment in parse_template(data)? {
+                if let Segment::Placeholder(placeholder) = segment
+                    && !variables.contains(&placeholder.name)
+                {
+                    variables.push(placeholder.name);
+                }
+            }
+        }
+    }
+    Ok(variables)
+}
+
+pub fn bind_commands(commands: &[ZplCommand], record: &Record) -> Result<Vec<ZplCommand>, String> {
+    let mut bound = commands.to_vec();
+    for index in template_fields(commands)? {
+        if let ZplCommand::FieldData { data } = &mut bound[index] {
+            *data = render_template(data, record)
+                .map_err(|e| format!("Command #{}: {}", index + 1, e))?;
+        }
+    }
+    Ok(bound)
+}
+
+pub fn records_from_csv(contents: &str) -> Result<Vec<Record>, String> {
+    let mut reader = csv::Reader::from_reader(contents.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(|e| format!("Failed to read CSV headers: {}", e))?
+        .iter()
+        .map(|header| header.trim().to_string())
+        .collect();
+
+    reader
+        .records()
+        .map(|result| {
+            let row = result.map_err(|e| format!("Failed to read CSV record: {}", e))?;
+            Ok(headers
+                .iter()
+                .cloned()
+                .zip(row.iter().map(str::to_string))
+                .collect())
+        })
+        .collect()
+}
+
+pub fn records_from_json(contents: &str) -> Result<Vec<Record>, String> {
+    let value: Value =
+        serde_json::from_str(contents).map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    match value {
+        Value::Array(values) => values.iter().map(record_from_json).collect(),
+        value => Ok(vec![record_from_json(&value)?]),
+    }
+}
+
+pub fn record_from_json(value: &Value) -> Result<Record, String> {
+    if !value.is_object() {
+        return Err(format!("Expected a JSON object, found: {}", value));
+    }
+    let mut record = Record::new();
+    flatten_json("", value, &mut record);
+    Ok(record)
+}
+
+fn flatten_json(prefix: &str, value: &Value, record: &mut Record) {
+    let key = |name: &str| {
+        if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", prefix, name)
+        }
+    };
+    match value {
+        Value::Object(fields) => {
+            for (name, value) in fields {
+                flatten_json(&key(name), value, record);
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                flatten_json(&key(&index.to_string()), value, record);
+            }
+        }
+        Value::String(text) => {
+            record.insert(prefix.to_string(), text.clone());
+        }
+        Value::Null => {}
+        value => {
+            record.insert(prefix.to_string(), value.to_string());
+        }
+    }
+}
+
+fn parse_date(value: &str) -> Result<NaiveDateTime, String> {
+    let value = value.trim();
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Ok(date.naive_local());
+    }
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| {
+            DATE_ONLY_FORMATS.iter().find_map(|format| {
+                NaiveDate::parse_from_str(value, format)
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })
+        })
+        .ok_or_else(|| format!("Invalid date: {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> Record {
+        Record::from([
+            ("order_id".to_string(), "42".to_string()),
+            ("name".to_string(), "chicken bowl".to_string()),
+            (
+                "promise_time".to_string(),
+                "2025-11-19 17:05:00".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn filters_apply_in_order() {
+        assert_eq!(
+            render_template(
+                "#{{order_id | pad:6}} {{name | upper}} {{promise_time | date:%m/%d %H:%M}}",
+                &record()
+            )
+            .unwrap(),
+            "#000042 CHICKEN BOWL 11/19 17:05"
+        );
+        assert_eq!(
+            render_template("{{note | default:N/A | lower}}", &record()).unwrap(),
+            "n/a"
+        );
+    }
+
+    #[test]
+    fn missing_values_and_bad_templates_are_errors() {
+        assert!(render_template("{{note}}", &record()).is_err());
+        assert!(render_template("{{name | bogus}}", &record()).is_err());
+        assert!(render_template("{{name", &record()).is_err());
+    }
+
+    #[test]
+    fn bind_commands_fills_field_data() {
+        let commands = vec![
+            ZplCommand::FieldOrigin { x: 0, y: 0 },
+            ZplCommand::FieldData {
+                data: "Order {{order_id}}".to_string(),
+            },
+        ];
+        assert_eq!(template_variables(&commands).unwrap(), vec!["order_id"]);
+        assert_eq!(
+            bind_commands(&commands, &record()).unwrap()[1],
+            ZplCommand::FieldData {
+                data: "Order 42".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_templates_report_the_command() {
+        let commands = vec![
+            ZplCommand::StartFormat,
+            ZplCommand::FieldData {
+                data: "{{name | bogus}}".to_string(),
+            },
+        ];
+        let error = template_fields(&commands).unwrap_err();
+        assert!(error.starts_with("Command #2:"), "{}", error);
+        assert_eq!(bind_commands(&commands, &record()).unwrap_err(), error);
+    }
+
+    #[test]
+    fn json_records_flatten_nested_keys() {
+        let records =
+            records_from_json(r#"[{"id": 7, "customer": {"city": "Denver"}, "tags": ["a"]}]"#)
+                .unwrap();
+        assert_eq!(records[0]["id"], "7");
+        assert_eq!(records[0]["customer.city"], "Denver");
+        assert_eq!(records[0]["tags.0"], "a");
+    }
+
+    #[test]
+    fn csv_records_are_keyed_by_header() {
+        let records = records_from_csv("order_id, name\n1,Steak\n").unwrap();
+        assert_eq!(records[0]["name"], "Steak");
+    }
+}
@@ -0,0 +1,350 @@
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use crate::zpl::ZplCommand;
+
+const ELLIPSIS: &str = "...";
+const SCALABLE_FIT_PERCENT: u32 = 90;
+
+static SCALABLE_FONT: OnceLock<FontRef<'static>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ZplFont {
+    #[default]
+    Scalable,
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+}
+
+impl ZplFont {
+    pub fn all() -> [ZplFont; 9] {
+        [
+            ZplFont::Scalable,
+            ZplFont::A,
+            ZplFont::B,
+            ZplFont::C,
+            ZplFont::D,
+            ZplFont::E,
+            ZplFont::F,
+            ZplFont::G,
+            ZplFont::H,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ZplFont::Scalable => "0 (Scalable)",
+            ZplFont::A => "A (9x5)",
+            ZplFont::B => "B (11x7)",
+            ZplFont::C => "C (18x10)",
+            ZplFont::D => "D (18x10)",
+            ZplFont::E => "E (28x15 OCR-B)",
+            ZplFont::F => "F (26x13)",
+            ZplFont::G => "G (60x40)",
+            ZplFont::H => "H (21x13 OCR-A)",
+        }
+    }
+
+    pub fn is_scalable(&self) -> bool {
+        *self == ZplFont::Scalable
+    }
+
+    pub fn bitmap_matrix(&self) -> Option<(u32, u32, u32)> {
+        match self {
+            ZplFont::Scalable => None,
+            ZplFont::A => Some((9, 5, 1)),
+            ZplFont::B => Some((11, 7, 2)),
+            ZplFont::C | ZplFont::D => Some((18, 10, 2)),
+            ZplFont::E => Some((28, 15, 5)),
+            ZplFont::F => Some((26, 13, 3)),
+            ZplFont::G => Some((60, 40, 8)),
+            ZplFont::H => Some((21, 13, 6)),
+        }
+    }
+}
+
+impl fmt::Display for ZplFont {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZplFont::Scalable => write!(f, "0"),
+            ZplFont::A => write!(f, "A"),
+            ZplFont::B => write!(f, "B"),
+            ZplFont::C => write!(f, "C"),
+            ZplFont::D => write!(f, "D"),
+            ZplFont::E => write!(f, "E"),
+            ZplFont::F => write!(f, "F"),
+            ZplFont::G => write!(f, "G"),
+            ZplFont::H => write!(f, "H"),
+        }
+    }
+}
+
+impl FromStr for ZplFont {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "0" => Ok(ZplFont::Scalable),
+            "A" => Ok(ZplFont::A),
+            "B" => Ok(ZplFont::B),
+            "C" => Ok(ZplFont::C),
+            "D" => Ok(ZplFont::D),
+            "E" => Ok(ZplFont::E),
+            "F" => Ok(ZplFont::F),
+            "G" => Ok(ZplFont::G),
+            "H" => Ok(ZplFont::H),
+            other => Err(format!("Invalid font: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextStyle {
+    pub font: ZplFont,
+    pub height: u32,
+    pub width: u32,
+}
+
+impl TextStyle {
+    pub fn new(font: ZplFont, height: u32, width: u32) -> Self {
+        Self {
+            font,
+            height,
+            width,
+        }
+    }
+
+    pub fn scalable(height: u32, width: u32) -> Self {
+        Self::new(ZplFont::Scalable, height, width)
+    }
+
+    pub fn measure(&self, text: &str) -> u32 {
+        match self.font.bitmap_matrix() {
+            None => scalable_text_width(text, self.height, self.width),
+            Some((_, base_width, gap)) => {
+                let (_, magnification) = self.bitmap_magnification();
+                let count = text.chars().count() as u32;
+                (count * (base_width + gap)).saturating_sub(gap) * magnification
+            }
+        }
+    }
+
+    /// Width the fitting helpers may fill. Scalable text is measured with a stand-in font, so it
+    /// only gets `SCALABLE_FIT_PERCENT` of `max_width`; bitmap fonts use their exact metrics.
+    pub fn fit_width(&self, max_width: u32) -> u32 {
+        match self.font.bitmap_matrix() {
+            None => (max_width as u64 * SCALABLE_FIT_PERCENT as u64 / 100) as u32,
+            Some(_) => max_width,
+        }
+    }
+
+    pub fn line_height(&self) -> u32 {
+        match self.font.bitmap_matrix() {
+            None => self.height.max(1),
+            Some((base_height, _, _)) => base_height * self.bitmap_magnification().0,
+        }
+    }
+
+    pub fn scaled(&self, height: u32) -> Self {
+        let width = if self.height == 0 {
+            self.width
+        } else {
+            (self.width as u64 * height as u64 / self.height as u64) as u32
+        };
+        Self::new(self.font, height, width)
+    }
+
+    fn bitmap_magnification(&self) -> (u32, u32) {
+        let Some((base_height, base_width, _)) = self.font.bitmap_matrix() else {
+            return (1, 1);
+        };
+        let magnify = |value: u32, base: u32| ((value + base / 2) / base).clamp(1, 10);
+        let height = magnify(self.height, base_height);
+        let width = if self.width == 0 {
+            height
+        } else {
+            magnify(self.width, base_width)
+        };
+        (height, width)
+    }
+}
+
+/// Stand-in for printer font 0 (CG Triumvirate Bold Condensed). Ubuntu Light has different
+/// advances, so widths measured with it are estimates of what the printer produces.
+pub fn scalable_font() -> &'static FontRef<'static> {
+    SCALABLE_FONT.get_or_init(|| {
+        FontRef::try_from_slice(epaint_default_fonts::UBUNTU_LIGHT)
+            .expect("bundled font should be valid")
+    })
+}
+
+pub fn scalable_font_scale(height: u32, width: u32) -> PxScale {
+    let height = height.max(1) as f32;
+    let width = if width == 0 { height } else { width as f32 };
+    PxScale {
+        x: width,
+        y: height,
+    }
+}
+
+pub fn scalable_text_width(text: &str, height: u32, width: u32) -> u32 {
+    let font = scalable_font();
+    let scaled = font.as_scaled(scalable_font_scale(height, width));
+    let mut caret = 0.0;
+    let mut previous = None;
+    for character in text.chars() {
+        let glyph_id = font.glyph_id(character);
+        if let Some(previous_id) = previous {
+            caret += scaled.kern(previous_id, glyph_id);
+        }
+        caret += scaled.h_advance(glyph_id);
+        previous = Some(glyph_id);
+    }
+    caret.ceil() as u32
+}
+
+/// Shrinks `style` until `text` fits. Scalable widths are estimates, so only `fit_width` of
+/// `max_width` is used.
+pub fn shrink_to_fit(text: &str, style: TextStyle, max_width: u32, min_height: u32) -> TextStyle {
+    let mut fitted = style;
+    while fitted.measure(text) > fitted.fit_width(max_width) && fitted.height > min_height.max(1) {
+        let step = match fitted.font.bitmap_matrix() {
+            Some((base_height, _, _)) => base_height,
+            None => 1,
+        };
+        fitted = style.scaled(fitted.height.saturating_sub(step).max(min_height.max(1)));
+    }
+    fitted
+}
+
+/// Cuts `text` and appends "..." until it fits within `fit_width` of `max_width`.
+pub fn truncate_to_fit(text: &str, style: TextStyle, max_width: u32) -> String {
+    let max_width = style.fit_width(max_width);
+    if style.measure(text) <= max_width {
+        return text.to_string();
+    }
+    let characters: Vec<char> = text.chars().collect();
+    for length in (0..characters.len()).rev() {
+        let candidate = format!(
+            "{}{}",
+            characters[..length].iter().collect::<String>().trim_end(),
+            ELLIPSIS
+        );
+        if style.measure(&candidate) <= max_width {
+            return candidate;
+        }
+    }
+    String::new()
+}
+
+/// Breaks `text` at spaces and `\&` into lines within `fit_width` of `max_width`. A word longer
+/// than the line is kept whole.
+pub fn wrap_lines(text: &str, style: TextStyle, max_width: u32) -> Vec<String> {
+    let max_width = style.fit_width(max_width);
+    let mut lines = Vec::new();
+
+    for paragraph in text.split("\\&") {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if style.measure(&candidate) <= max_width || current.is_empty() {
+                current = candidate;
+            } else {
+                lines.push(current);
+                current = word.to_string();
+            }
+        }
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Shrinks `style` until the wrapped text fits the box and returns the matching `^FB`. Scalable
+/// widths are estimates, so lines are kept within `fit_width` of `box_width`.
+pub fn fit_field_block(
+    text: &str,
+    style: TextStyle,
+    box_width: u32,
+    box_height: u32,
+    line_spacing: i32,
+    min_height: u32,
+) -> (TextStyle, ZplCommand) {
+    let lines_fitting = |style: &TextStyle| {
+        let line_height = (style.line_height() as i64 + line_spacing as i64).max(1);
+        ((box_height as i64 - style.line_height() as i64) / line_height + 1).max(1) as u32
+    };
+
+    let mut fitted = style;
+    loop {
+        let lines = wrap_lines(text, fitted, box_width);
+        let widest = lines.iter().map(|line| fitted.measure(line)).max();
+        let fits = lines.len() as u32 <= lines_fitting(&fitted)
+            && widest.is_none_or(|width| width <= fitted.fit_width(box_width));
+        if fits || fitted.height <= min_height.max(1) {
+            break;
+        }
+        let step = match fitted.font.bitmap_matrix() {
+            Some((base_height, _, _)) => base_height,
+            None => 1,
+        };
+        fitted = style.scaled(fitted.height.saturating_sub(step).max(min_height.max(1)));
+    }
+
+    let block = ZplCommand::FieldBlock {
+        width: box_width,
+        max_lines: lines_fitting(&fitted),
+        line_spacing,
+        justification: 'L',
+    };
+    (fitted, block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bitmap_text_wraps_at_spaces_and_line_breaks() {
+        let style = TextStyle::new(ZplFont::A, 9, 5);
+        assert_eq!(style.measure("ONE TWO"), 41);
+        assert_eq!(
+            wrap_lines("ONE TWO THREE\\&FOUR", style, 50),
+            vec!["ONE TWO", "THREE", "FOUR"]
+        );
+        assert_eq!(wrap_lines("UNBREAKABLE", style, 20), vec!["UNBREAKABLE"]);
+    }
+
+    #[test]
+    fn bitmap_text_truncates_with_ellipsis() {
+        let style = TextStyle::new(ZplFont::A, 9, 5);
+        assert_eq!(truncate_to_fit("HELLO WORLD", style, 65), "HELLO WORLD");
+        assert_eq!(truncate_to_fit("HELLO WORLD", style, 40), "HEL...");
+        assert_eq!(truncate_to_fit("HELLO WORLD", style, 10), "");
+    }
+
+    #[test]
+    fn scalable_text_keeps_a_safety_margin() {
+        let style = TextStyle::scalable(30, 30);
+        let text = "Green Chili Salsa";
+        let width = style.measure(text);
+        assert!(truncate_to_fit(text, style, width).ends_with(ELLIPSIS));
+        assert_eq!(wrap_lines(text, style, width).len(), 2);
+        let roomy = width * 100 / SCALABLE_FIT_PERCENT + 1;
+        assert_eq!(truncate_to_fit(text, style, roomy), text);
+        assert_eq!(shrink_to_fit(text, style, roomy, 10), style);
+    }
+}
@@ -0,0 +1,316 @@
+use image::imageops::FilterType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::graphic::graphic_to_image;
+use crate::zpl::{ZplCommand, image_to_zpl_hex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Resolution {
+    Dpmm6,
+    #[default]
+    Dpmm8,
+    Dpmm12,
+    Dpmm24,
+}
+
+impl Resolution {
+    pub fn all() -> [Resolution; 4] {
+        [
+            Resolution::Dpmm6,
+            Resolution::Dpmm8,
+            Resolution::Dpmm12,
+            Resolution::Dpmm24,
+        ]
+    }
+
+    pub fn dpmm(&self) -> u8 {
+        match self {
+            Resolution::Dpmm6 => 6,
+            Resolution::Dpmm8 => 8,
+            Resolution::Dpmm12 => 12,
+            Resolution::Dpmm24 => 24,
+        }
+    }
+
+    pub fn dpi(&self) -> u32 {
+        match self {
+            Resolution::Dpmm6 => 152,
+            Resolution::Dpmm8 => 203,
+            Resolution::Dpmm12 => 300,
+            Resolution::Dpmm24 => 600,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Resolution::Dpmm6 => "152 dpi (6 dpmm)",
+            Resolution::Dpmm8 => "203 dpi (8 dpmm)",
+            Resolution::Dpmm12 => "300 dpi (12 dpmm)",
+            Resolution::Dpmm24 => "600 dpi (24 dpmm)",
+        }
+    }
+
+    pub fn dots(&self, length: Length) -> u32 {
+        length.to_dots(*self)
+    }
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}dpmm", self.dpmm())
+    }
+}
+
+impl FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_lowercase();
+        let number = normalized
+            .strip_suffix("dpmm")
+            .or_else(|| normalized.strip_suffix("dpi"))
+            .unwrap_or(&normalized)
+            .trim();
+        Resolution::all()
+            .into_iter()
+            .find(|resolution| {
+                number == resolution.dpmm().to_string() || number == resolution.dpi().to_string()
+            })
+            .ok_or_else(|| format!("Invalid resolution: {}", value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Length {
+    Dots(u32),
+    Millimeters(f32),
+    Inches(f32),
+}
+
+impl Length {
+    pub fn dots(dots: u32) -> Self {
+        Length::Dots(dots)
+    }
+
+    pub fn mm(millimeters: f32) -> Self {
+        Length::Millimeters(millimeters)
+    }
+
+    pub fn inches(inches: f32) -> Self {
+        Length::Inches(inches)
+    }
+
+    pub fn to_dots(self, resolution: Resolution) -> u32 {
+        match self {
+            Length::Dots(dots) => dots,
+            _ => (self.to_mm(resolution) * resolution.dpmm() as f32)
+                .round()
+                .max(0.0) as u32,
+        }
+    }
+
+    pub fn to_mm(self, resolution: Resolution) -> f32 {
+        match self {
+            Length::Dots(dots) => dots as f32 / resolution.dpmm() as f32,
+            Length::Millimeters(millimeters) => millimeters,
+            Length::Inches(inches) => inches * 25.4,
+        }
+    }
+
+    pub fn to_inches(self, resolution: Resolution) -> f32 {
+        self.to_mm(resolution) / 25.4
+    }
+}
+
+impl From<u32> for Length {
+    fn from(dots: u32) -> Self {
+        Length::Dots(dots)
+    }
+}
+
+impl fmt::Display for Length {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Length::Dots(dots) => write!(f, "{}", dots),
+            Length::Millimeters(millimeters) => write!(f, "{}mm", millimeters),
+            Length::Inches(inches) => write!(f, "{}in", inches),
+        }
+    }
+}
+
+impl FromStr for Length {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized = value.trim().to_lowercase();
+        let invalid = || format!("Invalid length: {}", value);
+        if let Some(number) = normalized.strip_suffix("mm") {
+            number
+                .trim()
+                .parse()
+                .map(Length::Millimeters)
+                .map_err(|_| invalid())
+        } else if let Some(number) = normalized
+            .strip_suffix("in")
+            .or_else(|| normalized.strip_suffix('"'))
+        {
+            number
+                .trim()
+                .parse()
+                .map(Length::Inches)
+                .map_err(|_| invalid())
+        } else {
+            normalized
+                .strip_suffix("dots")
+                .unwrap_or(&normalized)
+                .trim()
+                .parse()
+                .map(Length::Dots)
+                .map_err(|_| invalid())
+        }
+    }
+}
+
+pub fn rescale_commands(
+    commands: &[ZplCommand],
+    from: Resolution,
+    to: Resolution,
+) -> Vec<ZplCommand> {
+    if from == to {
+        return commands.to_vec();
+    }
+    commands
+        .iter()
+        .map(|command| rescale_command(command, from, to))
+        .collect()
+}
+
+pub fn rescale_command(command: &ZplCommand, from: Resolution, to: Resolution) -> ZplCommand {
+    let ratio = to.dpmm() as f64 / from.dpmm() as f64;
+    let scale = |value: u32| {
+        if value == 0 {
+            0
+        } else {
+            ((value as f64 * ratio).round() as u32).max(1)
+        }
+    };
+    let scale_signed = |value: i32| (value as f64 * ratio).round() as i32;
+
+    let mut command = command.clone();
+    match &mut command {
+        ZplCommand::PrintWidth { width } => *width = scale(*width),
+        ZplCommand::LabelLength { length } => *length = scale(*length),
+        ZplCommand::LabelHome { x, y }
+        | ZplCommand::FieldOrigin { x, y }
+        | ZplCommand::FieldTypeset { x, y, .. } => {
+            *x = scale(*x);
+            *y = scale(*y);
+        }
+        ZplCommand::LabelShift { shift } => *shift = scale_signed(*shift).clamp(-9999, 9999),
+        ZplCommand::LabelTop { offset } => *offset = scale_signed(*offset).clamp(-120, 120),
+        ZplCommand::Font { height, width, .. } => {
+            *height = scale(*height);
+            *width = scale(*width);
+        }
+        ZplCommand::ChangeFont { size, .. } => *size = scale(*size),
+        ZplCommand::GraphicBox {
+            width,
+            height,
+            thickness,
+            ..
+        }
+        | ZplCommand::GraphicEllipse {
+            width,
+            height,
+            thickness,
+            ..
+        }
+        | ZplCommand::GraphicDiagonalLine {
+            width,
+            height,
+            thickness,
+            ..
+        } => {
+            *width = scale(*width);
+            *height = scale(*height);
+            *thickness = scale(*thickness);
+        }
+        ZplCommand::GraphicCircle {
+            diameter,
+            thickness,
+            ..
+        } => {
+            *diameter = scale(*diameter);
+            *thickness = scale(*thickness);
+        }
+        ZplCommand::BarcodeFieldDefault { width, height, .. } => {
+            *width = scale(*width).clamp(1, 10);
+            *height = scale(*height);
+        }
+        ZplCommand::Code128Barcode { height, .. }
+        | ZplCommand::Code39Barcode { height, .. }
+        | ZplCommand::Ean13Barcode { height, .. }
+        | ZplCommand::UpcABarcode { height, .. }
+        | ZplCommand::Interleaved2of5Barcode { height, .. }
+        | ZplCommand::Ean8Barcode { height, .. }
+        | ZplCommand::DataMatrix { height, .. } => *height = scale(*height),
+        ZplCommand::Pdf417 { row_height, .. } => *row_height = scale(*row_height),
+        ZplCommand::QrCode { magnification, .. } => {
+            *magnification = scale(*magnification).clamp(1, 10)
+        }
+        ZplCommand::FieldBlock {
+            width,
+            line_spacing,
+            ..
+        } => {
+            *width = scale(*width);
+            *line_spacing = scale_signed(*line_spacing);
+        }
+        ZplCommand::GraphicField {
+            width,
+            height,
+            data,
+            ..
+        }
+        | ZplCommand::DownloadGraphic {
+            width,
+            height,
+            data,
+            ..
+        } => {
+            let (new_width, new_height) = (scale(*width), scale(*height));
+            if new_width > 0 && new_height > 0 {
+                let image = graphic_to_image(*width, *height, data).resize_exact(
+                    new_width,
+                    new_height,
+                    FilterType::Triangle,
+                );
+                *data = image_to_zpl_hex(&image, 128);
+                *width = new_width;
+                *height = new_height;
+            }
+        }
+        _ => {}
+    }
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_zpl;
+    use crate::zpl::commands_to_zpl;
+
+    #[test]
+    fn rescaled_label_offsets_stay_parseable() {
+        let commands = parse_zpl("^XA^LT100^LS-9000^FO100,50^FDX^FS^XZ").unwrap();
+        let rescaled = rescale_commands(&commands, Resolution::Dpmm8, Resolution::Dpmm12);
+        let reparsed = parse_zpl(&commands_to_zpl(&rescaled)).unwrap();
+        assert_eq!(reparsed, rescaled);
+        assert_eq!(reparsed[1], ZplCommand::LabelTop { offset: 120 });
+        assert_eq!(reparsed[2], ZplCommand::LabelShift { shift: -9999 });
+        assert_eq!(reparsed[3], ZplCommand::FieldOrigin { x: 150, y: 75 });
+    }
+}
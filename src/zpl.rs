@@ -1,24 +1,84 @@
-use image::{DynamicImage, GenericImageView, Rgba};
-use serde::{Deserialize, Serialize};
+use crate::graphic::{
+    ImageConversion, encode_graphic_data, extract_graphics, image_to_zpl_hex_with,
+};
+use crate::gs1::{Gs1Barcode, Gs1Error};
+use crate::text::ZplFont;
+use image::DynamicImage;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
 use std::fmt;
+use std::str::FromStr;
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ZplPrefix {
     Caret,
     Tilde,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl fmt::Display for ZplPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ZplPrefix::Caret => write!(f, "^"),
+            ZplPrefix::Tilde => write!(f, "~"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub enum ZplCommand {
     StartFormat,
     EndFormat,
+    PrintWidth {
+        width: u32,
+    },
+    LabelLength {
+        length: u32,
+    },
+    LabelHome {
+        x: u32,
+        y: u32,
+    },
+    LabelShift {
+        shift: i32,
+    },
+    LabelTop {
+        offset: i32,
+    },
+    PrintOrientation {
+        inverted: bool,
+    },
+    PrintRate {
+        print_speed: u32,
+        slew_speed: Option<u32>,
+        backfeed_speed: Option<u32>,
+    },
+    MediaDarkness {
+        adjustment: i32,
+    },
+    SetDarkness {
+        darkness: u32,
+    },
+    MediaType {
+        media_type: MediaType,
+    },
+    MediaTracking {
+        tracking: MediaTracking,
+    },
     FieldOrigin {
         x: u32,
         y: u32,
     },
+    FieldTypeset {
+        x: u32,
+        y: u32,
+        #[serde(default)]
+        justification: Option<u32>,
+    },
     Font {
+        #[serde(default)]
+        font: FontName,
         orientation: FontOrientation,
         height: u32,
         width: u32,
@@ -26,6 +86,43 @@ pub enum ZplCommand {
     FieldData {
         data: String,
     },
+    ChangeEncoding {
+        encoding: u32,
+    },
+    FieldClock {
+        primary: char,
+        secondary: Option<char>,
+        tertiary: Option<char>,
+    },
+    ClockMode {
+        mode: ClockMode,
+        language: Option<u32>,
+    },
+    SetTime {
+        month: u32,
+        day: u32,
+        year: u32,
+        hour: u32,
+        minute: u32,
+        second: u32,
+        twenty_four_hour: bool,
+    },
+    SerialNumber {
+        start: String,
+        increment: i64,
+        leading_zeros: bool,
+    },
+    SerializationField {
+        mask: String,
+        increment: String,
+    },
+    PrintQuantity {
+        quantity: u32,
+        pause_count: u32,
+        replicates: u32,
+        override_pause: bool,
+    },
+    #[default]
     FieldSeparator,
     GraphicBox {
         width: u32,
@@ -34,10 +131,39 @@ pub enum ZplCommand {
         color: Option<char>,
         rounding: Option<u8>,
     },
+    GraphicCircle {
+        diameter: u32,
+        thickness: u32,
+        color: Option<char>,
+    },
+    GraphicEllipse {
+        width: u32,
+        height: u32,
+        thickness: u32,
+        color: Option<char>,
+    },
+    GraphicDiagonalLine {
+        width: u32,
+        height: u32,
+        thickness: u32,
+        color: Option<char>,
+        orientation: char,
+    },
+    FieldReverse,
     ChangeFont {
         font: String,
         size: u32,
     },
+    FontAlias {
+        alias: char,
+        path: String,
+    },
+    DownloadFont {
+        path: String,
+        #[serde(default)]
+        method: FontDownload,
+        data: String,
+    },
     FieldOrientation {
         rotation: FieldRotation,
     },
@@ -52,33 +178,144 @@ pub enum ZplCommand {
         print_interpretation: bool,
         print_above: bool,
         check_digit: bool,
-        mode: FieldOrientation,
+        mode: Code128Mode,
+    },
+    Code39Barcode {
+        orientation: FieldOrientation,
+        check_digit: bool,
+        height: u32,
+        print_interpretation: bool,
+        print_above: bool,
+    },
+    Ean13Barcode {
+        orientation: FieldOrientation,
+        height: u32,
+        print_interpretation: bool,
+        print_above: bool,
+    },
+    UpcABarcode {
+        orientation: FieldOrientation,
+        height: u32,
+        print_interpretation: bool,
+        print_above: bool,
+        print_check_digit: bool,
+    },
+    Interleaved2of5Barcode {
+        orientation: FieldOrientation,
+        height: u32,
+        print_interpretation: bool,
+        print_above: bool,
+        check_digit: bool,
+    },
+    Ean8Barcode {
+        orientation: FieldOrientation,
+        height: u32,
+        print_interpretation: bool,
+        print_above: bool,
+    },
+    QrCode {
+        model: u8,
+        magnification: u32,
+        error_correction: QrErrorCorrection,
+        mask: u8,
+    },
+    DataMatrix {
+        orientation: FieldOrientation,
+        height: u32,
+        quality: u32,
+        columns: Option<u32>,
+        rows: Option<u32>,
+        format_id: u8,
+        escape: char,
+    },
+    Pdf417 {
+        orientation: FieldOrientation,
+        row_height: u32,
+        security_level: u8,
+        columns: Option<u32>,
+        rows: Option<u32>,
+        truncate: bool,
     },
     GraphicField {
         width: u32,
         height: u32,
         data: String,
+        #[serde(default)]
+        encoding: GraphicEncoding,
     },
     DownloadGraphic {
         name: String,
         width: u32,
         height: u32,
         data: String,
+        #[serde(default)]
+        encoding: GraphicEncoding,
     },
     RecallGraphic {
         name: String,
         magnification_x: u32,
         magnification_y: u32,
     },
-    MediaModeDelayed,
-    MediaModeTearOff,
-    CutNow,
+    DownloadFormat {
+        name: String,
+    },
+    RecallFormat {
+        name: String,
+    },
+    FieldNumber {
+        number: u32,
+    },
+    MediaMode {
+        mode: MediaMode,
+        prepeel: bool,
+    },
+    ImmediateCut {
+        cut: bool,
+    },
+    #[serde(alias = "CutNow")]
+    DelayedCut,
+    KioskValues {
+        cut_amount: u32,
+        cut_margin: u32,
+        present_type: KioskPresentType,
+        present_timeout: u32,
+        loop_length: u32,
+    },
     FieldBlock {
         width: u32,
         max_lines: u32,
         line_spacing: i32,
         justification: char,
     },
+    Comment {
+        text: String,
+    },
+    Raw {
+        prefix: ZplPrefix,
+        code: String,
+        params: String,
+    },
+}
+
+impl Serialize for ZplCommand {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ZplCommand::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ZplCommand {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = match Value::deserialize(deserializer)? {
+            Value::String(name) if name == "MediaModeDelayed" => {
+                json!({ "MediaMode": { "mode": MediaMode::DelayedCutter, "prepeel": false } })
+            }
+            Value::String(name) if name == "MediaModeTearOff" => {
+                json!({ "MediaMode": { "mode": MediaMode::TearOff, "prepeel": false } })
+            }
+            value => value,
+        };
+        ZplCommand::deserialize(value).map_err(D::Error::custom)
+    }
 }
 
 impl ZplCommand {
@@ -86,44 +323,231 @@ impl ZplCommand {
         match self {
             ZplCommand::StartFormat => "Start Format (^XA)",
             ZplCommand::EndFormat => "End Format (^XZ)",
+            ZplCommand::PrintWidth { .. } => "Print Width (^PW)",
+            ZplCommand::LabelLength { .. } => "Label Length (^LL)",
+            ZplCommand::LabelHome { .. } => "Label Home (^LH)",
+            ZplCommand::LabelShift { .. } => "Label Shift (^LS)",
+            ZplCommand::LabelTop { .. } => "Label Top (^LT)",
+            ZplCommand::PrintOrientation { .. } => "Print Orientation (^PO)",
+            ZplCommand::PrintRate { .. } => "Print Rate (^PR)",
+            ZplCommand::MediaDarkness { .. } => "Media Darkness (^MD)",
+            ZplCommand::SetDarkness { .. } => "Set Darkness (~SD)",
+            ZplCommand::MediaType { .. } => "Media Type (^MT)",
+            ZplCommand::MediaTracking { .. } => "Media Tracking (^MN)",
             ZplCommand::FieldOrigin { .. } => "Field Origin (^FO)",
-            ZplCommand::Font { .. } => "Font (^A0)",
+            ZplCommand::FieldTypeset { .. } => "Field Typeset (^FT)",
+            ZplCommand::Font { .. } => "Font (^A)",
+            ZplCommand::FontAlias { .. } => "Font Alias (^CW)",
+            ZplCommand::DownloadFont {
+                method: FontDownload::TrueType,
+                ..
+            } => "Download Font (~DY)",
+            ZplCommand::DownloadFont {
+                method: FontDownload::Unbounded,
+                ..
+            } => "Download Font (~DU)",
             ZplCommand::FieldData { .. } => "Field Data (^FD)",
+            ZplCommand::ChangeEncoding { .. } => "Change Encoding (^CI)",
+            ZplCommand::FieldClock { .. } => "Field Clock (^FC)",
+            ZplCommand::ClockMode { .. } => "Clock Mode (^SL)",
+            ZplCommand::SetTime { .. } => "Set Time (^ST)",
+            ZplCommand::SerialNumber { .. } => "Serial Number (^SN)",
+            ZplCommand::SerializationField { .. } => "Serialization Field (^SF)",
+            ZplCommand::PrintQuantity { .. } => "Print Quantity (^PQ)",
             ZplCommand::FieldSeparator => "Field Separator (^FS)",
             ZplCommand::GraphicBox { .. } => "Graphic Box (^GB)",
+            ZplCommand::GraphicCircle { .. } => "Graphic Circle (^GC)",
+            ZplCommand::GraphicEllipse { .. } => "Graphic Ellipse (^GE)",
+            ZplCommand::GraphicDiagonalLine { .. } => "Graphic Diagonal Line (^GD)",
+            ZplCommand::FieldReverse => "Field Reverse (^FR)",
             ZplCommand::ChangeFont { .. } => "Change Font (^CF)",
             ZplCommand::FieldOrientation { .. } => "Field Orientation (^FW)",
             ZplCommand::BarcodeFieldDefault { .. } => "Barcode Field Default (^BY)",
             ZplCommand::Code128Barcode { .. } => "Code 128 Barcode (^BC)",
+            ZplCommand::Code39Barcode { .. } => "Code 39 Barcode (^B3)",
+            ZplCommand::Ean13Barcode { .. } => "EAN-13 Barcode (^BE)",
+            ZplCommand::UpcABarcode { .. } => "UPC-A Barcode (^BU)",
+            ZplCommand::Interleaved2of5Barcode { .. } => "Interleaved 2 of 5 Barcode (^B2)",
+            ZplCommand::Ean8Barcode { .. } => "EAN-8 Barcode (^B8)",
+            ZplCommand::QrCode { .. } => "QR Code (^BQ)",
+            ZplCommand::DataMatrix { .. } => "DataMatrix (^BX)",
+            ZplCommand::Pdf417 { .. } => "PDF417 (^B7)",
             ZplCommand::GraphicField { .. } => "Graphic Field (^GFA)",
             ZplCommand::DownloadGraphic { .. } => "Download Graphic (~DG)",
             ZplCommand::RecallGraphic { .. } => "Recall Graphic (^XG)",
-            ZplCommand::MediaModeDelayed => "Media Mode Delayed (^MMD)",
-            ZplCommand::MediaModeTearOff => "Media Mode Tear-off (^MMT)",
-            ZplCommand::CutNow => "Cut Now (~JK)",
+            ZplCommand::DownloadFormat { .. } => "Download Format (^DF)",
+            ZplCommand::RecallFormat { .. } => "Recall Format (^XF)",
+            ZplCommand::FieldNumber { .. } => "Field Number (^FN)",
+            ZplCommand::MediaMode { .. } => "Media Mode (^MM)",
+            ZplCommand::ImmediateCut { .. } => "Cut Now (^CN)",
+            ZplCommand::DelayedCut => "Delayed Cut (~JK)",
+            ZplCommand::KioskValues { .. } => "Kiosk Values (^KV)",
             ZplCommand::FieldBlock { .. } => "Field Block (^FB)",
+            ZplCommand::Comment { .. } => "Comment (^FX)",
+            ZplCommand::Raw { .. } => "Raw Command",
         }
     }
 
+    pub fn is_serialized(&self) -> bool {
+        matches!(
+            self,
+            ZplCommand::SerialNumber { .. } | ZplCommand::SerializationField { .. }
+        )
+    }
+
+    pub fn is_barcode(&self) -> bool {
+        matches!(
+            self,
+            ZplCommand::Code128Barcode { .. }
+                | ZplCommand::Code39Barcode { .. }
+                | ZplCommand::Ean13Barcode { .. }
+                | ZplCommand::UpcABarcode { .. }
+                | ZplCommand::Interleaved2of5Barcode { .. }
+                | ZplCommand::Ean8Barcode { .. }
+                | ZplCommand::QrCode { .. }
+                | ZplCommand::DataMatrix { .. }
+                | ZplCommand::Pdf417 { .. }
+        )
+    }
+
     pub fn all_command_types() -> Vec<(&'static str, ZplCommand)> {
         vec![
             ("Start Format (^XA)", ZplCommand::StartFormat),
             ("End Format (^XZ)", ZplCommand::EndFormat),
+            ("Print Width (^PW)", ZplCommand::PrintWidth { width: 812 }),
+            (
+                "Label Length (^LL)",
+                ZplCommand::LabelLength { length: 1218 },
+            ),
+            ("Label Home (^LH)", ZplCommand::LabelHome { x: 0, y: 0 }),
+            ("Label Shift (^LS)", ZplCommand::LabelShift { shift: 0 }),
+            ("Label Top (^LT)", ZplCommand::LabelTop { offset: 0 }),
+            (
+                "Print Orientation (^PO)",
+                ZplCommand::PrintOrientation { inverted: false },
+            ),
+            (
+                "Print Rate (^PR)",
+                ZplCommand::PrintRate {
+                    print_speed: 4,
+                    slew_speed: None,
+                    backfeed_speed: None,
+                },
+            ),
+            (
+                "Media Darkness (^MD)",
+                ZplCommand::MediaDarkness { adjustment: 0 },
+            ),
+            (
+                "Set Darkness (~SD)",
+                ZplCommand::SetDarkness { darkness: 15 },
+            ),
+            (
+                "Media Type (^MT)",
+                ZplCommand::MediaType {
+                    media_type: MediaType::DirectThermal,
+                },
+            ),
+            (
+                "Media Tracking (^MN)",
+                ZplCommand::MediaTracking {
+                    tracking: MediaTracking::WebSensing,
+                },
+            ),
             ("Field Origin (^FO)", ZplCommand::FieldOrigin { x: 0, y: 0 }),
             (
-                "Font (^A0)",
+                "Field Typeset (^FT)",
+                ZplCommand::FieldTypeset {
+                    x: 0,
+                    y: 0,
+                    justification: None,
+                },
+            ),
+            (
+                "Font (^A)",
                 ZplCommand::Font {
+                    font: FontName::default(),
                     orientation: FontOrientation::Normal,
                     height: 30,
                     width: 30,
                 },
             ),
+            (
+                "Font Alias (^CW)",
+                ZplCommand::FontAlias {
+                    alias: 'Z',
+                    path: "E:ARIAL.TTF".to_string(),
+                },
+            ),
+            (
+                "Download Font (~DY)",
+                ZplCommand::DownloadFont {
+                    path: "E:ARIAL.TTF".to_string(),
+                    method: FontDownload::TrueType,
+                    data: String::new(),
+                },
+            ),
             (
                 "Field Data (^FD)",
                 ZplCommand::FieldData {
                     data: String::new(),
                 },
             ),
+            (
+                "Change Encoding (^CI)",
+                ZplCommand::ChangeEncoding { encoding: 28 },
+            ),
+            (
+                "Field Clock (^FC)",
+                ZplCommand::FieldClock {
+                    primary: '%',
+                    secondary: None,
+                    tertiary: None,
+                },
+            ),
+            (
+                "Clock Mode (^SL)",
+                ZplCommand::ClockMode {
+                    mode: ClockMode::StartTime,
+                    language: None,
+                },
+            ),
+            (
+                "Set Time (^ST)",
+                ZplCommand::SetTime {
+                    month: 1,
+                    day: 1,
+                    year: 2025,
+                    hour: 0,
+                    minute: 0,
+                    second: 0,
+                    twenty_four_hour: true,
+                },
+            ),
+            (
+                "Serial Number (^SN)",
+                ZplCommand::SerialNumber {
+                    start: "001".to_string(),
+                    increment: 1,
+                    leading_zeros: true,
+                },
+            ),
+            (
+                "Serialization Field (^SF)",
+                ZplCommand::SerializationField {
+                    mask: "DDD".to_string(),
+                    increment: "1".to_string(),
+                },
+            ),
+            (
+                "Print Quantity (^PQ)",
+                ZplCommand::PrintQuantity {
+                    quantity: 1,
+                    pause_count: 0,
+                    replicates: 0,
+                    override_pause: false,
+                },
+            ),
             ("Field Separator (^FS)", ZplCommand::FieldSeparator),
             (
                 "Graphic Box (^GB)",
@@ -135,12 +559,121 @@ impl ZplCommand {
                     rounding: None,
                 },
             ),
+            (
+                "Code 39 Barcode (^B3)",
+                ZplCommand::Code39Barcode {
+                    orientation: FieldOrientation::Normal,
+                    check_digit: false,
+                    height: 80,
+                    print_interpretation: true,
+                    print_above: false,
+                },
+            ),
+            (
+                "EAN-13 Barcode (^BE)",
+                ZplCommand::Ean13Barcode {
+                    orientation: FieldOrientation::Normal,
+                    height: 80,
+                    print_interpretation: true,
+                    print_above: false,
+                },
+            ),
+            (
+                "UPC-A Barcode (^BU)",
+                ZplCommand::UpcABarcode {
+                    orientation: FieldOrientation::Normal,
+                    height: 80,
+                    print_interpretation: true,
+                    print_above: false,
+                    print_check_digit: true,
+                },
+            ),
+            (
+                "Interleaved 2 of 5 Barcode (^B2)",
+                ZplCommand::Interleaved2of5Barcode {
+                    orientation: FieldOrientation::Normal,
+                    height: 80,
+                    print_interpretation: true,
+                    print_above: false,
+                    check_digit: false,
+                },
+            ),
+            (
+                "EAN-8 Barcode (^B8)",
+                ZplCommand::Ean8Barcode {
+                    orientation: FieldOrientation::Normal,
+                    height: 80,
+                    print_interpretation: true,
+                    print_above: false,
+                },
+            ),
+            (
+                "QR Code (^BQ)",
+                ZplCommand::QrCode {
+                    model: 2,
+                    magnification: 5,
+                    error_correction: QrErrorCorrection::High,
+                    mask: 7,
+                },
+            ),
+            (
+                "DataMatrix (^BX)",
+                ZplCommand::DataMatrix {
+                    orientation: FieldOrientation::Normal,
+                    height: 6,
+                    quality: 200,
+                    columns: None,
+                    rows: None,
+                    format_id: 6,
+                    escape: '~',
+                },
+            ),
+            (
+                "PDF417 (^B7)",
+                ZplCommand::Pdf417 {
+                    orientation: FieldOrientation::Normal,
+                    row_height: 10,
+                    security_level: 5,
+                    columns: None,
+                    rows: None,
+                    truncate: false,
+                },
+            ),
+            (
+                "Graphic Circle (^GC)",
+                ZplCommand::GraphicCircle {
+                    diameter: 100,
+                    thickness: 1,
+                    color: None,
+                },
+            ),
+            (
+                "Graphic Ellipse (^GE)",
+                ZplCommand::GraphicEllipse {
+                    width: 150,
+                    height: 100,
+                    thickness: 1,
+                    color: None,
+                },
+            ),
+            (
+                "Graphic Diagonal Line (^GD)",
+                ZplCommand::GraphicDiagonalLine {
+                    width: 100,
+                    height: 100,
+                    thickness: 3,
+                    color: None,
+                    orientation: 'R',
+                },
+            ),
+            ("Field Reverse (^FR)", ZplCommand::FieldReverse),
             (
                 "Graphic Field (^GFA)",
                 ZplCommand::GraphicField {
                     width: 32,
                     height: 32,
                     data: String::new(),
+                    encoding: GraphicEncoding::Auto,
                 },
             ),
             (
@@ -150,6 +683,7 @@ impl ZplCommand {
                     width: 32,
                     height: 32,
                     data: String::new(),
+                    encoding: GraphicEncoding::Auto,
                 },
             ),
             (
@@ -160,6 +694,38 @@ impl ZplCommand {
                     magnification_y: 1,
                 },
             ),
+            (
+                "Download Format (^DF)",
+                ZplCommand::DownloadFormat {
+                    name: "R:FORMAT.ZPL".to_string(),
+                },
+            ),
+            (
+                "Recall Format (^XF)",
+                ZplCommand::RecallFormat {
+                    name: "R:FORMAT.ZPL".to_string(),
+                },
+            ),
+            ("Field Number (^FN)", ZplCommand::FieldNumber { number: 1 }),
+            (
+                "Media Mode (^MM)",
+                ZplCommand::MediaMode {
+                    mode: MediaMode::TearOff,
+                    prepeel: false,
+                },
+            ),
+            ("Cut Now (^CN)", ZplCommand::ImmediateCut { cut: true }),
+            ("Delayed Cut (~JK)", ZplCommand::DelayedCut),
+            (
+                "Kiosk Values (^KV)",
+                ZplCommand::KioskValues {
+                    cut_amount: 0,
+                    cut_margin: 9,
+                    present_type: KioskPresentType::Eject,
+                    present_timeout: 6,
+                    loop_length: 400,
+                },
+            ),
             (
                 "Field Block (^FB)",
                 ZplCommand::FieldBlock {
@@ -169,6 +735,20 @@ impl ZplCommand {
                     justification: 'C',
                 },
             ),
+            (
+                "Comment (^FX)",
+                ZplCommand::Comment {
+                    text: String::new(),
+                },
+            ),
+            (
+                "Raw Command",
+                ZplCommand::Raw {
+                    prefix: ZplPrefix::Caret,
+                    code: String::new(),
+                    params: String::new(),
+                },
+            ),
         ]
     }
 
@@ -176,13 +756,140 @@ impl ZplCommand {
         match self {
             ZplCommand::StartFormat => "^XA".to_string(),
             ZplCommand::EndFormat => "^XZ".to_string(),
+            ZplCommand::PrintWidth { width } => format!("^PW{}", width),
+            ZplCommand::LabelLength { length } => format!("^LL{}", length),
+            ZplCommand::LabelHome { x, y } => format!("^LH{},{}", x, y),
+            ZplCommand::LabelShift { shift } => format!("^LS{}", shift),
+            ZplCommand::LabelTop { offset } => format!("^LT{}", offset),
+            ZplCommand::PrintOrientation { inverted } => {
+                format!("^PO{}", if *inverted { "I" } else { "N" })
+            }
+            ZplCommand::PrintRate {
+                print_speed,
+                slew_speed,
+                backfeed_speed,
+            } => {
+                let mut result = format!("^PR{}", print_speed);
+                if slew_speed.is_some() || backfeed_speed.is_some() {
+                    result.push_str(&format!(",{}", optional_param(slew_speed)));
+                }
+                if backfeed_speed.is_some() {
+                    result.push_str(&format!(",{}", optional_param(backfeed_speed)));
+                }
+                result
+            }
+            ZplCommand::MediaDarkness { adjustment } => format!("^MD{}", adjustment),
+            ZplCommand::SetDarkness { darkness } => format!("~SD{:02}", darkness),
+            ZplCommand::MediaType { media_type } => format!("^MT{}", media_type),
+            ZplCommand::MediaTracking { tracking } => format!("^MN{}", tracking),
             ZplCommand::FieldOrigin { x, y } => format!("^FO{},{}", x, y),
+            ZplCommand::FieldTypeset {
+                x,
+                y,
+                justification,
+            } => match justification {
+                Some(justification) => format!("^FT{},{},{}", x, y, justification),
+                None => format!("^FT{},{}", x, y),
+            },
+            ZplCommand::Font {
+                font: FontName::Device(path),
+                orientation,
+                height,
+                width,
+            } => format!("^A@{},{},{},{}", orientation, height, width, path),
             ZplCommand::Font {
+                font: FontName::Letter(letter),
                 orientation,
                 height,
                 width,
-            } => format!("^A0{},{},{}", orientation, height, width),
-            ZplCommand::FieldData { data } => format!("^FD{}", data),
+            } => format!("^A{}{},{},{}", letter, orientation, height, width),
+            ZplCommand::FontAlias { alias, path } => format!("^CW{},{}", alias, path),
+            ZplCommand::DownloadFont { path, method, data } => {
+                let hex: String = data
+                    .chars()
+                    .filter(|c| c.is_ascii_hexdigit())
+                    .map(|c| c.to_ascii_uppercase())
+                    .collect();
+                let size = hex.len() / 2;
+                match method {
+                    FontDownload::TrueType => {
+                        let (name, extension) = path.rsplit_once('.').unwrap_or((path, "TTF"));
+                        let extension = if extension.eq_ignore_ascii_case("TTE") {
+                            'E'
+                        } else {
+                            'T'
+                        };
+                        format!("~DY{},A,{},{},,{}", name, extension, size, hex)
+                    }
+                    FontDownload::Unbounded => format!("~DU{},{},{}", path, size, hex),
+                }
+            }
+            ZplCommand::FieldData { data } => {
+                if needs_hex_escape(data) {
+                    format!("^FH^FD{}", hex_escape(data))
+                } else {
+                    format!("^FD{}", data)
+                }
+            }
+            ZplCommand::ChangeEncoding { encoding } => format!("^CI{}", encoding),
+            ZplCommand::FieldClock {
+                primary,
+                secondary,
+                tertiary,
+            } => {
+                let mut result = format!("^FC{}", primary);
+                if secondary.is_some() || tertiary.is_some() {
+                    result.push(',');
+                    result.extend(*secondary);
+                }
+                if let Some(tertiary) = tertiary {
+                    result.push(',');
+                    result.push(*tertiary);
+                }
+                result
+            }
+            ZplCommand::ClockMode { mode, language } => match language {
+                Some(language) => format!("^SL{},{}", mode, language),
+                None => format!("^SL{}", mode),
+            },
+            ZplCommand::SetTime {
+                month,
+                day,
+                year,
+                hour,
+                minute,
+                second,
+                twenty_four_hour,
+            } => format!(
+                "^ST{:02},{:02},{:04},{:02},{:02},{:02},{}",
+                month,
+                day,
+                year,
+                hour,
+                minute,
+                second,
+                if *twenty_four_hour { "M" } else { "A" }
+            ),
+            ZplCommand::SerialNumber {
+                start,
+                increment,
+                leading_zeros,
+            } => format!("^SN{},{},{}", start, increment, yes_no(*leading_zeros)),
+            ZplCommand::SerializationField { mask, increment } => {
+                format!("^SF{},{}", mask, increment)
+            }
+            ZplCommand::PrintQuantity {
+                quantity,
+                pause_count,
+                replicates,
+                override_pause,
+            } => format!(
+                "^PQ{},{},{},{}",
+                quantity,
+                pause_count,
+                replicates,
+                yes_no(*override_pause)
+            ),
             ZplCommand::FieldSeparator => "^FS".to_string(),
             ZplCommand::GraphicBox {
                 width,
@@ -202,21 +909,59 @@ impl ZplCommand {
                 }
                 result
             }
-            ZplCommand::ChangeFont { font, size } => format!("^CF{},{}", font, size),
-            ZplCommand::FieldOrientation { rotation } => format!("^FW{}", rotation),
-            ZplCommand::BarcodeFieldDefault {
+            ZplCommand::GraphicCircle {
+                diameter,
+                thickness,
+                color,
+            } => {
+                let mut result = format!("^GC{},{}", diameter, thickness);
+                if let Some(color_char) = color {
+                    result.push_str(&format!(",{}", color_char));
+                }
+                result
+            }
+            ZplCommand::GraphicEllipse {
                 width,
-                ratio,
-                height,
-            } => format!("^BY{},{},{}", width, ratio, height),
-            ZplCommand::Code128Barcode {
-                orientation,
                 height,
-                print_interpretation,
-                print_above,
-                check_digit,
-                mode,
-            } => format!(
+                thickness,
+                color,
+            } => {
+                let mut result = format!("^GE{},{},{}", width, height, thickness);
+                if let Some(color_char) = color {
+                    result.push_str(&format!(",{}", color_char));
+                }
+                result
+            }
+            ZplCommand::GraphicDiagonalLine {
+                width,
+                height,
+                thickness,
+                color,
+                orientation,
+            } => format!(
+                "^GD{},{},{},{},{}",
+                width,
+                height,
+                thickness,
+                color.map(String::from).unwrap_or_default(),
+                orientation
+            ),
+            ZplCommand::FieldReverse => "^FR".to_string(),
+            ZplCommand::ChangeFont { font, size } => format!("^CF{},{}", font, size),
+            ZplCommand::FieldOrientation { rotation } => format!("^FW{}", rotation),
+            ZplCommand::BarcodeFieldDefault {
+                width,
+                ratio,
+                height,
+            } => format!("^BY{},{},{}", width, ratio, height),
+            ZplCommand::Code128Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+                mode,
+            } => format!(
                 "^BC{},{},{},{},{},{}",
                 orientation,
                 height,
@@ -225,22 +970,129 @@ impl ZplCommand {
                 if *check_digit { "Y" } else { "N" },
                 mode
             ),
+            ZplCommand::Code39Barcode {
+                orientation,
+                check_digit,
+                height,
+                print_interpretation,
+                print_above,
+            } => format!(
+                "^B3{},{},{},{},{}",
+                orientation,
+                yes_no(*check_digit),
+                height,
+                yes_no(*print_interpretation),
+                yes_no(*print_above)
+            ),
+            ZplCommand::Ean13Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => format!(
+                "^BE{},{},{},{}",
+                orientation,
+                height,
+                yes_no(*print_interpretation),
+                yes_no(*print_above)
+            ),
+            ZplCommand::UpcABarcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                print_check_digit,
+            } => format!(
+                "^BU{},{},{},{},{}",
+                orientation,
+                height,
+                yes_no(*print_interpretation),
+                yes_no(*print_above),
+                yes_no(*print_check_digit)
+            ),
+            ZplCommand::Interleaved2of5Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+                check_digit,
+            } => format!(
+                "^B2{},{},{},{},{}",
+                orientation,
+                height,
+                yes_no(*print_interpretation),
+                yes_no(*print_above),
+                yes_no(*check_digit)
+            ),
+            ZplCommand::Ean8Barcode {
+                orientation,
+                height,
+                print_interpretation,
+                print_above,
+            } => format!(
+                "^B8{},{},{},{}",
+                orientation,
+                height,
+                yes_no(*print_interpretation),
+                yes_no(*print_above)
+            ),
+            ZplCommand::QrCode {
+                model,
+                magnification,
+                error_correction,
+                mask,
+            } => format!(
+                "^BQN,{},{},{},{}",
+                model, magnification, error_correction, mask
+            ),
+            ZplCommand::DataMatrix {
+                orientation,
+                height,
+                quality,
+                columns,
+                rows,
+                format_id,
+                escape,
+            } => format!(
+                "^BX{},{},{},{},{},{},{}",
+                orientation,
+                height,
+                quality,
+                optional_param(columns),
+                optional_param(rows),
+                format_id,
+                escape
+            ),
+            ZplCommand::Pdf417 {
+                orientation,
+                row_height,
+                security_level,
+                columns,
+                rows,
+                truncate,
+            } => format!(
+                "^B7{},{},{},{},{},{}",
+                orientation,
+                row_height,
+                security_level,
+                optional_param(columns),
+                optional_param(rows),
+                if *truncate { "Y" } else { "N" }
+            ),
             ZplCommand::GraphicField {
                 width,
                 height,
                 data,
+                encoding,
             } => {
-                let bytes_per_row = (width + 7) / 8;
+                let bytes_per_row = width.div_ceil(8);
                 let total_bytes = bytes_per_row * height;
-                let clean_data = data
-                    .replace(",", "")
-                    .replace(" ", "")
-                    .replace("\n", "")
-                    .replace("\r", "")
-                    .to_uppercase();
                 format!(
                     "^GFA,{},{},{},{}",
-                    total_bytes, total_bytes, bytes_per_row, clean_data
+                    total_bytes,
+                    total_bytes,
+                    bytes_per_row,
+                    encode_graphic_data(data, bytes_per_row, *encoding)
                 )
             }
             ZplCommand::DownloadGraphic {
@@ -248,18 +1100,16 @@ impl ZplCommand {
                 width,
                 height,
                 data,
+                encoding,
             } => {
-                let bytes_per_row = (width + 7) / 8;
+                let bytes_per_row = width.div_ceil(8);
                 let total_bytes = bytes_per_row * height;
-                let clean_data = data
-                    .replace(",", "")
-                    .replace(" ", "")
-                    .replace("\n", "")
-                    .replace("\r", "")
-                    .to_uppercase();
                 format!(
                     "~DG{},{},{},{}",
-                    name, total_bytes, bytes_per_row, clean_data
+                    name,
+                    total_bytes,
+                    bytes_per_row,
+                    encode_graphic_data(data, bytes_per_row, *encoding)
                 )
             }
             ZplCommand::RecallGraphic {
@@ -269,23 +1119,97 @@ impl ZplCommand {
             } => {
                 format!("^XG{},{},{}", name, magnification_x, magnification_y)
             }
-            ZplCommand::MediaModeDelayed => "^MMD".to_string(),
-            ZplCommand::MediaModeTearOff => "^MMT".to_string(),
-            ZplCommand::CutNow => "~JK".to_string(),
+            ZplCommand::DownloadFormat { name } => format!("^DF{}", name),
+            ZplCommand::RecallFormat { name } => format!("^XF{}", name),
+            ZplCommand::FieldNumber { number } => format!("^FN{}", number),
+            ZplCommand::MediaMode { mode, prepeel } => {
+                if *prepeel {
+                    format!("^MM{},Y", mode)
+                } else {
+                    format!("^MM{}", mode)
+                }
+            }
+            ZplCommand::ImmediateCut { cut } => format!("^CN{}", if *cut { 1 } else { 0 }),
+            ZplCommand::DelayedCut => "~JK".to_string(),
+            ZplCommand::KioskValues {
+                cut_amount,
+                cut_margin,
+                present_type,
+                present_timeout,
+                loop_length,
+            } => format!(
+                "^KV{},{},{},{},{}",
+                cut_amount, cut_margin, present_type, present_timeout, loop_length
+            ),
             ZplCommand::FieldBlock {
                 width,
                 max_lines,
                 line_spacing,
                 justification,
-            } => format!("^FB{},{},{},{}", width, max_lines, line_spacing, justification),
+            } => format!(
+                "^FB{},{},{},{}",
+                width, max_lines, line_spacing, justification
+            ),
+            ZplCommand::Comment { text } => format!("^FX{}", text),
+            ZplCommand::Raw {
+                prefix,
+                code,
+                params,
+            } => format!("{}{}{}", prefix, code, params),
         }
     }
 }
 
-impl Default for ZplCommand {
-    fn default() -> Self {
-        ZplCommand::FieldSeparator
+fn yes_no(value: bool) -> &'static str {
+    if value { "Y" } else { "N" }
+}
+
+fn optional_param(value: &Option<u32>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+pub fn label_dimensions(commands: &[ZplCommand]) -> (Option<u32>, Option<u32>) {
+    let mut width = None;
+    let mut length = None;
+    for command in commands {
+        match command {
+            ZplCommand::PrintWidth { width: value } if *value > 0 => width = Some(*value),
+            ZplCommand::LabelLength { length: value } if *value > 0 => length = Some(*value),
+            _ => {}
+        }
     }
+    (width, length)
+}
+
+pub fn dots_to_inches(dots: u32, dpmm: u8) -> f32 {
+    dots as f32 / (dpmm.max(1) as f32 * 25.4)
+}
+
+pub fn inches_to_dots(inches: f32, dpmm: u8) -> u32 {
+    (inches * 25.4 * dpmm as f32 + 0.001) as u32
+}
+
+fn is_reserved_character(character: char) -> bool {
+    character == '^' || character == '~' || character.is_control() || !character.is_ascii()
+}
+
+fn needs_hex_escape(data: &str) -> bool {
+    data.chars().any(is_reserved_character)
+}
+
+fn hex_escape(data: &str) -> String {
+    let mut escaped = String::with_capacity(data.len());
+    for character in data.chars() {
+        if character == '_' || is_reserved_character(character) {
+            let mut buffer = [0; 4];
+            for byte in character.encode_utf8(&mut buffer).bytes() {
+                escaped.push_str(&format!("_{:02X}", byte));
+            }
+        } else {
+            escaped.push(character);
+        }
+    }
+    escaped
 }
 
 pub fn commands_to_zpl(commands: &[ZplCommand]) -> String {
@@ -296,6 +1220,99 @@ pub fn commands_to_zpl(commands: &[ZplCommand]) -> String {
         .join("\n")
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FontName {
+    Letter(char),
+    Device(String),
+}
+
+impl FontName {
+    pub fn zpl_font(&self) -> Option<ZplFont> {
+        match self {
+            FontName::Letter(letter) => letter.to_string().parse().ok(),
+            FontName::Device(_) => None,
+        }
+    }
+}
+
+impl Default for FontName {
+    fn default() -> Self {
+        FontName::Letter('0')
+    }
+}
+
+impl From<ZplFont> for FontName {
+    fn from(font: ZplFont) -> Self {
+        FontName::Letter(font.to_string().chars().next().unwrap_or('0'))
+    }
+}
+
+impl From<char> for FontName {
+    fn from(letter: char) -> Self {
+        FontName::Letter(letter.to_ascii_uppercase())
+    }
+}
+
+impl fmt::Display for FontName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FontName::Letter(letter) => write!(f, "{}", letter),
+            FontName::Device(path) => write!(f, "{}", path),
+        }
+    }
+}
+
+impl FromStr for FontName {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let mut chars = value.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) if is_font_letter(letter) => {
+                Ok(FontName::Letter(letter.to_ascii_uppercase()))
+            }
+            _ if is_font_path(value) => Ok(FontName::Device(value.to_string())),
+            _ => Err(format!("Invalid font name: {}", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum FontDownload {
+    #[default]
+    TrueType,
+    Unbounded,
+}
+
+impl FontDownload {
+    pub fn all() -> [FontDownload; 2] {
+        [FontDownload::TrueType, FontDownload::Unbounded]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FontDownload::TrueType => "TrueType (~DY)",
+            FontDownload::Unbounded => "Unbounded TrueType (~DU)",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            FontDownload::TrueType => "TTF",
+            FontDownload::Unbounded => "FNT",
+        }
+    }
+}
+
+pub fn is_font_letter(letter: char) -> bool {
+    letter.is_ascii_alphanumeric()
+}
+
+pub fn is_font_path(path: &str) -> bool {
+    matches!(path.split_once(':'), Some((device, name)) if device.len() == 1 && !name.is_empty())
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FontOrientation {
     Normal,
@@ -315,6 +1332,20 @@ impl fmt::Display for FontOrientation {
     }
 }
 
+impl FromStr for FontOrientation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "N" => Ok(FontOrientation::Normal),
+            "R" => Ok(FontOrientation::Rotated90),
+            "I" => Ok(FontOrientation::Rotated180),
+            "B" => Ok(FontOrientation::Rotated270),
+            other => Err(format!("Invalid orientation: {}", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FieldOrientation {
     Normal,
@@ -334,6 +1365,20 @@ impl fmt::Display for FieldOrientation {
     }
 }
 
+impl FromStr for FieldOrientation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "N" => Ok(FieldOrientation::Normal),
+            "R" => Ok(FieldOrientation::Rotated90),
+            "I" => Ok(FieldOrientation::Rotated180),
+            "B" => Ok(FieldOrientation::Rotated270),
+            other => Err(format!("Invalid orientation: {}", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum FieldRotation {
     Normal,
@@ -353,6 +1398,417 @@ impl fmt::Display for FieldRotation {
     }
 }
 
+impl FromStr for FieldRotation {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "N" => Ok(FieldRotation::Normal),
+            "R" => Ok(FieldRotation::Rotated90),
+            "I" => Ok(FieldRotation::Rotated180),
+            "B" => Ok(FieldRotation::Rotated270),
+            other => Err(format!("Invalid orientation: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GraphicEncoding {
+    #[default]
+    Auto,
+    Hex,
+    Acs,
+    Z64,
+    B64,
+}
+
+impl GraphicEncoding {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GraphicEncoding::Auto => "Auto (smallest)",
+            GraphicEncoding::Hex => "ASCII Hex",
+            GraphicEncoding::Acs => "ACS Run-Length",
+            GraphicEncoding::Z64 => "Z64",
+            GraphicEncoding::B64 => "B64",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockMode {
+    StartTime,
+    TimeNow,
+    Tolerance(u32),
+}
+
+impl fmt::Display for ClockMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClockMode::StartTime => write!(f, "S"),
+            ClockMode::TimeNow => write!(f, "T"),
+            ClockMode::Tolerance(minutes) => write!(f, "{}", minutes),
+        }
+    }
+}
+
+impl FromStr for ClockMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "S" => Ok(ClockMode::StartTime),
+            "T" => Ok(ClockMode::TimeNow),
+            other => match other.parse::<u32>() {
+                Ok(minutes) if (1..=999).contains(&minutes) => Ok(ClockMode::Tolerance(minutes)),
+                _ => Err(format!("Invalid clock mode: {}", other)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaMode {
+    TearOff,
+    PeelOff,
+    Rewind,
+    Applicator,
+    Cutter,
+    DelayedCutter,
+    Rfid,
+    LinerlessPeel,
+    LinerlessRewind,
+    Kiosk,
+}
+
+impl MediaMode {
+    pub fn all() -> [MediaMode; 10] {
+        [
+            MediaMode::TearOff,
+            MediaMode::PeelOff,
+            MediaMode::Rewind,
+            MediaMode::Applicator,
+            MediaMode::Cutter,
+            MediaMode::DelayedCutter,
+            MediaMode::Rfid,
+            MediaMode::LinerlessPeel,
+            MediaMode::LinerlessRewind,
+            MediaMode::Kiosk,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MediaMode::TearOff => "Tear-off",
+            MediaMode::PeelOff => "Peel-off",
+            MediaMode::Rewind => "Rewind",
+            MediaMode::Applicator => "Applicator",
+            MediaMode::Cutter => "Cutter",
+            MediaMode::DelayedCutter => "Delayed Cutter",
+            MediaMode::Rfid => "RFID",
+            MediaMode::LinerlessPeel => "Linerless Peel",
+            MediaMode::LinerlessRewind => "Linerless Rewind",
+            MediaMode::Kiosk => "Kiosk",
+        }
+    }
+}
+
+impl fmt::Display for MediaMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let code = match self {
+            MediaMode::TearOff => "T",
+            MediaMode::PeelOff => "P",
+            MediaMode::Rewind => "R",
+            MediaMode::Applicator => "A",
+            MediaMode::Cutter => "C",
+            MediaMode::DelayedCutter => "D",
+            MediaMode::Rfid => "F",
+            MediaMode::LinerlessPeel => "L",
+            MediaMode::LinerlessRewind => "U",
+            MediaMode::Kiosk => "K",
+        };
+        write!(f, "{}", code)
+    }
+}
+
+impl FromStr for MediaMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "T" => Ok(MediaMode::TearOff),
+            "P" => Ok(MediaMode::PeelOff),
+            "R" => Ok(MediaMode::Rewind),
+            "A" => Ok(MediaMode::Applicator),
+            "C" => Ok(MediaMode::Cutter),
+            "D" => Ok(MediaMode::DelayedCutter),
+            "F" => Ok(MediaMode::Rfid),
+            "L" => Ok(MediaMode::LinerlessPeel),
+            "U" => Ok(MediaMode::LinerlessRewind),
+            "K" => Ok(MediaMode::Kiosk),
+            other => Err(format!("Invalid media mode: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KioskPresentType {
+    Eject,
+    Retract,
+    NoMotion,
+}
+
+impl fmt::Display for KioskPresentType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KioskPresentType::Eject => write!(f, "J"),
+            KioskPresentType::Retract => write!(f, "R"),
+            KioskPresentType::NoMotion => write!(f, "N"),
+        }
+    }
+}
+
+impl FromStr for KioskPresentType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "J" => Ok(KioskPresentType::Eject),
+            "R" => Ok(KioskPresentType::Retract),
+            "N" => Ok(KioskPresentType::NoMotion),
+            other => Err(format!("Invalid kiosk present type: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaType {
+    ThermalTransfer,
+    DirectThermal,
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaType::ThermalTransfer => write!(f, "T"),
+            MediaType::DirectThermal => write!(f, "D"),
+        }
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "T" => Ok(MediaType::ThermalTransfer),
+            "D" => Ok(MediaType::DirectThermal),
+            other => Err(format!("Invalid media type: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MediaTracking {
+    Continuous,
+    ContinuousVariableLength,
+    WebSensing,
+    MarkSensing,
+    AutoDetect,
+}
+
+impl fmt::Display for MediaTracking {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MediaTracking::Continuous => write!(f, "N"),
+            MediaTracking::ContinuousVariableLength => write!(f, "V"),
+            MediaTracking::WebSensing => write!(f, "Y"),
+            MediaTracking::MarkSensing => write!(f, "M"),
+            MediaTracking::AutoDetect => write!(f, "A"),
+        }
+    }
+}
+
+impl FromStr for MediaTracking {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "N" => Ok(MediaTracking::Continuous),
+            "V" => Ok(MediaTracking::ContinuousVariableLength),
+            "Y" | "W" => Ok(MediaTracking::WebSensing),
+            "M" => Ok(MediaTracking::MarkSensing),
+            "A" => Ok(MediaTracking::AutoDetect),
+            other => Err(format!("Invalid media tracking: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Code128Mode {
+    #[serde(alias = "Normal")]
+    NoSelection,
+    UccCase,
+    Automatic,
+    UccEan,
+}
+
+impl fmt::Display for Code128Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Code128Mode::NoSelection => write!(f, "N"),
+            Code128Mode::UccCase => write!(f, "U"),
+            Code128Mode::Automatic => write!(f, "A"),
+            Code128Mode::UccEan => write!(f, "D"),
+        }
+    }
+}
+
+impl FromStr for Code128Mode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "N" => Ok(Code128Mode::NoSelection),
+            "U" => Ok(Code128Mode::UccCase),
+            "A" => Ok(Code128Mode::Automatic),
+            "D" => Ok(Code128Mode::UccEan),
+            other => Err(format!("Invalid Code 128 mode: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QrErrorCorrection {
+    UltraHigh,
+    High,
+    Standard,
+    HighDensity,
+}
+
+impl fmt::Display for QrErrorCorrection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QrErrorCorrection::UltraHigh => write!(f, "H"),
+            QrErrorCorrection::High => write!(f, "Q"),
+            QrErrorCorrection::Standard => write!(f, "M"),
+            QrErrorCorrection::HighDensity => write!(f, "L"),
+        }
+    }
+}
+
+impl FromStr for QrErrorCorrection {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_uppercase().as_str() {
+            "H" => Ok(QrErrorCorrection::UltraHigh),
+            "Q" => Ok(QrErrorCorrection::High),
+            "M" => Ok(QrErrorCorrection::Standard),
+            "L" => Ok(QrErrorCorrection::HighDensity),
+            other => Err(format!("Invalid QR error correction level: {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QrInputMode {
+    Automatic,
+    Numeric,
+    Alphanumeric,
+    Byte,
+    Kanji,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QrFieldData {
+    pub error_correction: QrErrorCorrection,
+    pub input_mode: QrInputMode,
+    pub data: String,
+}
+
+impl QrFieldData {
+    pub fn new(error_correction: QrErrorCorrection, data: impl Into<String>) -> Self {
+        Self {
+            error_correction,
+            input_mode: QrInputMode::Automatic,
+            data: data.into(),
+        }
+    }
+}
+
+impl fmt::Display for QrFieldData {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.input_mode {
+            QrInputMode::Automatic => write!(f, "{}A,{}", self.error_correction, self.data),
+            QrInputMode::Numeric => write!(f, "{}M,N{}", self.error_correction, self.data),
+            QrInputMode::Alphanumeric => write!(f, "{}M,A{}", self.error_correction, self.data),
+            QrInputMode::Byte => write!(
+                f,
+                "{}M,B{:04}{}",
+                self.error_correction,
+                self.data.len(),
+                self.data
+            ),
+            QrInputMode::Kanji => write!(f, "{}M,K{}", self.error_correction, self.data),
+        }
+    }
+}
+
+impl FromStr for QrFieldData {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (prefix, data) = value
+            .split_once(',')
+            .ok_or_else(|| format!("QR field data is missing its prefix: {}", value))?;
+        let mut prefix_chars = prefix.chars();
+        let error_correction = match prefix_chars.next() {
+            Some(level) => level.to_string().parse::<QrErrorCorrection>()?,
+            None => QrErrorCorrection::High,
+        };
+
+        let (input_mode, data) = match prefix_chars.next().map(|mode| mode.to_ascii_uppercase()) {
+            Some('A') | None => (QrInputMode::Automatic, data.to_string()),
+            Some('M') => {
+                let mut data_chars = data.chars();
+                match data_chars.next().map(|mode| mode.to_ascii_uppercase()) {
+                    Some('N') => (QrInputMode::Numeric, data_chars.collect()),
+                    Some('A') => (QrInputMode::Alphanumeric, data_chars.collect()),
+                    Some('K') => (QrInputMode::Kanji, data_chars.collect()),
+                    Some('B') => {
+                        let rest: String = data_chars.collect();
+                        let length = rest
+                            .get(..4)
+                            .and_then(|digits| digits.parse::<usize>().ok())
+                            .ok_or_else(|| {
+                                "QR byte mode requires a four digit length".to_string()
+                            })?;
+                        let bytes = &rest.as_bytes()[4..];
+                        let bytes = &bytes[..length.min(bytes.len())];
+                        (
+                            QrInputMode::Byte,
+                            String::from_utf8_lossy(bytes).into_owned(),
+                        )
+                    }
+                    other => {
+                        return Err(format!(
+                            "Invalid QR character mode: {}",
+                            other.map(String::from).unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+            Some(other) => return Err(format!("Invalid QR input mode: {}", other)),
+        };
+
+        Ok(Self {
+            error_correction,
+            input_mode,
+            data,
+        })
+    }
+}
+
 pub struct ZplLabel {
     commands: Vec<ZplCommand>,
 }
@@ -364,13 +1820,54 @@ impl ZplLabel {
         }
     }
 
+    pub fn print_width(mut self, width: u32) -> Self {
+        self.commands.push(ZplCommand::PrintWidth { width });
+        self
+    }
+
+    pub fn label_length(mut self, length: u32) -> Self {
+        self.commands.push(ZplCommand::LabelLength { length });
+        self
+    }
+
+    pub fn label_home(mut self, x: u32, y: u32) -> Self {
+        self.commands.push(ZplCommand::LabelHome { x, y });
+        self
+    }
+
+    pub fn label_shift(mut self, shift: i32) -> Self {
+        self.commands.push(ZplCommand::LabelShift { shift });
+        self
+    }
+
+    pub fn label_top(mut self, offset: i32) -> Self {
+        self.commands.push(ZplCommand::LabelTop { offset });
+        self
+    }
+
+    pub fn print_orientation(mut self, inverted: bool) -> Self {
+        self.commands
+            .push(ZplCommand::PrintOrientation { inverted });
+        self
+    }
+
     pub fn field_origin(mut self, x: u32, y: u32) -> Self {
         self.commands.push(ZplCommand::FieldOrigin { x, y });
         self
     }
 
+    pub fn field_typeset(mut self, x: u32, y: u32) -> Self {
+        self.commands.push(ZplCommand::FieldTypeset {
+            x,
+            y,
+            justification: None,
+        });
+        self
+    }
+
     pub fn font(mut self, orientation: FontOrientation, height: u32, width: u32) -> Self {
         self.commands.push(ZplCommand::Font {
+            font: FontName::default(),
             orientation,
             height,
             width,
@@ -378,12 +1875,41 @@ impl ZplLabel {
         self
     }
 
+    pub fn named_font(
+        mut self,
+        font: impl Into<FontName>,
+        orientation: FontOrientation,
+        height: u32,
+        width: u32,
+    ) -> Self {
+        self.commands.push(ZplCommand::Font {
+            font: font.into(),
+            orientation,
+            height,
+            width,
+        });
+        self
+    }
+
+    pub fn font_alias(mut self, alias: char, path: impl Into<String>) -> Self {
+        self.commands.push(ZplCommand::FontAlias {
+            alias,
+            path: path.into(),
+        });
+        self
+    }
+
     pub fn field_data(mut self, data: impl Into<String>) -> Self {
         self.commands
             .push(ZplCommand::FieldData { data: data.into() });
         self
     }
 
+    pub fn change_encoding(mut self, encoding: u32) -> Self {
+        self.commands.push(ZplCommand::ChangeEncoding { encoding });
+        self
+    }
+
     pub fn field_separator(mut self) -> Self {
         self.commands.push(ZplCommand::FieldSeparator);
         self
@@ -400,137 +1926,95 @@ impl ZplLabel {
         self
     }
 
+    pub fn graphic_circle(mut self, diameter: u32, thickness: u32) -> Self {
+        self.commands.push(ZplCommand::GraphicCircle {
+            diameter,
+            thickness,
+            color: None,
+        });
+        self
+    }
+
+    pub fn graphic_ellipse(mut self, width: u32, height: u32, thickness: u32) -> Self {
+        self.commands.push(ZplCommand::GraphicEllipse {
+            width,
+            height,
+            thickness,
+            color: None,
+        });
+        self
+    }
+
+    pub fn graphic_diagonal_line(
+        mut self,
+        width: u32,
+        height: u32,
+        thickness: u32,
+        orientation: char,
+    ) -> Self {
+        self.commands.push(ZplCommand::GraphicDiagonalLine {
+            width,
+            height,
+            thickness,
+            color: None,
+            orientation,
+        });
+        self
+    }
+
+    pub fn field_reverse(mut self) -> Self {
+        self.commands.push(ZplCommand::FieldReverse);
+        self
+    }
+
     pub fn graphic_field(mut self, width: u32, height: u32, data: impl Into<String>) -> Self {
         self.commands.push(ZplCommand::GraphicField {
             width,
             height,
             data: data.into(),
+            encoding: GraphicEncoding::Auto,
+        });
+        self
+    }
+
+    pub fn qr_code(
+        mut self,
+        magnification: u32,
+        error_correction: QrErrorCorrection,
+        data: impl Into<String>,
+    ) -> Self {
+        self.commands.push(ZplCommand::QrCode {
+            model: 2,
+            magnification,
+            error_correction,
+            mask: 7,
+        });
+        self.commands.push(ZplCommand::FieldData {
+            data: QrFieldData::new(error_correction, data).to_string(),
         });
         self
     }
 
+    pub fn gs1_128(
+        mut self,
+        barcode: &Gs1Barcode,
+        x: u32,
+        y: u32,
+        height: u32,
+        interpretation: bool,
+    ) -> Result<Self, Gs1Error> {
+        self.commands
+            .extend(barcode.to_commands(x, y, height, interpretation)?);
+        Ok(self)
+    }
+
     pub fn build(mut self) -> String {
         self.commands.push(ZplCommand::EndFormat);
         self.to_zpl()
     }
 
     fn to_zpl(&self) -> String {
-        self.commands
-            .iter()
-            .map(|cmd| match cmd {
-                ZplCommand::StartFormat => format!("^XA"),
-                ZplCommand::EndFormat => format!("^XZ"),
-                ZplCommand::FieldOrigin { x, y } => format!("^FO{},{}", x, y),
-                ZplCommand::Font {
-                    orientation,
-                    height,
-                    width,
-                } => {
-                    format!("^A0{},{},{}", orientation, height, width)
-                }
-                ZplCommand::FieldData { data } => format!("^FD{}", data),
-                ZplCommand::FieldSeparator => format!("^FS"),
-                ZplCommand::GraphicBox {
-                    width,
-                    height,
-                    thickness,
-                    color,
-                    rounding,
-                } => {
-                    let mut result = format!("^GB{},{},{}", width, height, thickness);
-                    if let Some(color_char) = color {
-                        result.push_str(&format!(",{}", color_char));
-                        if let Some(rounding_value) = rounding {
-                            result.push_str(&format!(",{}", rounding_value));
-                        }
-                    } else if let Some(rounding_value) = rounding {
-                        result.push_str(&format!(",,{}", rounding_value));
-                    }
-                    result
-                }
-                ZplCommand::ChangeFont { font, size } => format!("^CF{},{}", font, size),
-                ZplCommand::FieldOrientation { rotation } => format!("^FW{}", rotation),
-                ZplCommand::BarcodeFieldDefault {
-                    width,
-                    ratio,
-                    height,
-                } => {
-                    format!("^BY{},{},{}", width, ratio, height)
-                }
-                ZplCommand::Code128Barcode {
-                    orientation,
-                    height,
-                    print_interpretation,
-                    print_above,
-                    check_digit,
-                    mode,
-                } => {
-                    format!(
-                        "^BC{},{},{},{},{},{}",
-                        orientation,
-                        height,
-                        if *print_interpretation { "Y" } else { "N" },
-                        if *print_above { "Y" } else { "N" },
-                        if *check_digit { "Y" } else { "N" },
-                        mode
-                    )
-                }
-                ZplCommand::GraphicField {
-                    width,
-                    height,
-                    data,
-                } => {
-                    let bytes_per_row = (width + 7) / 8;
-                    let total_bytes = bytes_per_row * height;
-                    let clean_data = data
-                        .replace(",", "")
-                        .replace(" ", "")
-                        .replace("\n", "")
-                        .replace("\r", "")
-                        .to_uppercase();
-                    format!(
-                        "^GFA,{},{},{},{}",
-                        total_bytes, total_bytes, bytes_per_row, clean_data
-                    )
-                }
-                ZplCommand::DownloadGraphic {
-                    name,
-                    width,
-                    height,
-                    data,
-                } => {
-                    let bytes_per_row = (width + 7) / 8;
-                    let total_bytes = bytes_per_row * height;
-                    let clean_data = data
-                        .replace(",", "")
-                        .replace(" ", "")
-                        .replace("\n", "")
-                        .replace("\r", "")
-                        .to_uppercase();
-                    format!(
-                        "~DG{},{},{},{}",
-                        name, total_bytes, bytes_per_row, clean_data
-                    )
-                }
-                ZplCommand::RecallGraphic {
-                    name,
-                    magnification_x,
-                    magnification_y,
-                } => {
-                    format!("^XG{},{},{}", name, magnification_x, magnification_y)
-                }
-                ZplCommand::MediaModeDelayed => format!("^MMD"),
-                ZplCommand::MediaModeTearOff => format!("^MMT"),
-                ZplCommand::CutNow => format!("~JK"),
-                ZplCommand::FieldBlock {
-                    width,
-                    max_lines,
-                    line_spacing,
-                    justification,
-                } => format!("^FB{},{},{},{}", width, max_lines, line_spacing, justification),
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
+        commands_to_zpl(&self.commands)
     }
 }
 
@@ -541,41 +2025,13 @@ impl Default for ZplLabel {
 }
 
 pub fn image_to_zpl_hex(image: &DynamicImage, threshold: u8) -> String {
-    let width = image.width();
-    let height = image.height();
-    let bytes_per_row = ((width + 7) / 8) as usize;
-
-    let mut hex_lines = Vec::new();
-
-    for y in 0..height {
-        let mut row_bytes = vec![0u8; bytes_per_row];
-
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            let grayscale = rgb_to_grayscale(pixel);
-
-            if grayscale < threshold {
-                let byte_index = (x / 8) as usize;
-                let bit_index = 7 - (x % 8);
-                row_bytes[byte_index] |= 1 << bit_index;
-            }
-        }
-
-        let row_hex: String = row_bytes
-            .iter()
-            .map(|byte| format!("{:02X}", byte))
-            .collect();
-        hex_lines.push(row_hex);
-    }
-
-    hex_lines.join("")
-}
-
-fn rgb_to_grayscale(pixel: Rgba<u8>) -> u8 {
-    let r = pixel[0] as u32;
-    let g = pixel[1] as u32;
-    let b = pixel[2] as u32;
-    ((r * 299 + g * 587 + b * 114) / 1000) as u8
+    image_to_zpl_hex_with(
+        image,
+        &ImageConversion {
+            threshold,
+            ..ImageConversion::default()
+        },
+    )
 }
 
 pub fn create_graphic_field_from_image(image: &DynamicImage, threshold: u8) -> ZplCommand {
@@ -587,49 +2043,62 @@ pub fn create_graphic_field_from_image(image: &DynamicImage, threshold: u8) -> Z
         width,
         height,
         data,
+        encoding: GraphicEncoding::Auto,
     }
 }
 
 pub fn parse_graphic_field_from_zpl(zpl: &str) -> Option<(u32, u32, String)> {
-    let zpl_upper = zpl.to_uppercase();
-
-    if let Some(gf_start) = zpl_upper.find("^GF") {
-        let after_gf = &zpl[gf_start + 3..];
-
-        if after_gf.starts_with("A,") || after_gf.to_uppercase().starts_with("A,") {
-            let gfa_section = &zpl[gf_start + 5..];
-
-            let end_pos = gfa_section.find('^').unwrap_or(gfa_section.len());
-            let gfa_data = &gfa_section[..end_pos];
-            let parts: Vec<&str> = gfa_data.split(',').collect();
+    extract_graphics(zpl.as_bytes())
+        .ok()?
+        .into_iter()
+        .find_map(|command| match command {
+            ZplCommand::GraphicField {
+                width,
+                height,
+                data,
+                ..
+            } => Some((width, height, data)),
+            _ => None,
+        })
+}
 
-            if parts.len() >= 4 {
-                let total_bytes = parts[0].trim().parse::<u32>().ok()?;
-                let bytes_per_row = parts[2].trim().parse::<u32>().ok()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                let hex_data_parts: Vec<&str> = parts[3..]
-                    .iter()
-                    .flat_map(|s| s.split_whitespace())
-                    .collect();
-                let hex_data = hex_data_parts
-                    .join("")
-                    .replace(",", "")
-                    .replace(" ", "")
-                    .replace("\n", "")
-                    .replace("\r", "")
-                    .to_uppercase();
-
-                let height = if bytes_per_row > 0 {
-                    total_bytes / bytes_per_row
-                } else {
-                    0
-                };
-                let width = bytes_per_row * 8;
-
-                return Some((width, height, hex_data));
-            }
-        }
+    #[test]
+    fn legacy_media_and_cut_commands_deserialize() {
+        let commands: Vec<ZplCommand> =
+            serde_json::from_str(r#"["MediaModeDelayed", "MediaModeTearOff", "CutNow"]"#).unwrap();
+        assert_eq!(
+            commands,
+            vec![
+                ZplCommand::MediaMode {
+                    mode: MediaMode::DelayedCutter,
+                    prepeel: false,
+                },
+                ZplCommand::MediaMode {
+                    mode: MediaMode::TearOff,
+                    prepeel: false,
+                },
+                ZplCommand::DelayedCut,
+            ]
+        );
     }
 
-    None
+    #[test]
+    fn commands_round_trip_through_json() {
+        let commands = vec![
+            ZplCommand::ImmediateCut { cut: true },
+            ZplCommand::DelayedCut,
+            ZplCommand::FieldData {
+                data: "Hello".to_string(),
+            },
+        ];
+        let json = serde_json::to_string(&commands).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<ZplCommand>>(&json).unwrap(),
+            commands
+        );
+    }
 }